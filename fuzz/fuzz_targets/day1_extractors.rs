@@ -0,0 +1,14 @@
+#![no_main]
+
+use aoc_2023::challenge::day1;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|line: &str| {
+    let ascii_digits = day1::extract_first_last_ascii_digits(line);
+    let ascii_or_spelled_digits = day1::extract_first_last_ascii_or_spelled_digits(line);
+
+    // Every ascii digit is also found when spelled out digits count too
+    if ascii_digits.is_some() {
+        assert!(ascii_or_spelled_digits.is_some());
+    }
+});
@@ -0,0 +1,13 @@
+#![no_main]
+
+use aoc_2023::challenge::day2::CubeCollection;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|draw: &str| {
+    if let Ok(collection) = draw.parse::<CubeCollection>() {
+        assert_eq!(
+            collection.to_string().parse::<CubeCollection>(),
+            Ok(collection)
+        );
+    }
+});
@@ -0,0 +1,10 @@
+#![no_main]
+
+use aoc_2023::challenge::day2::Game;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|line: &str| {
+    if let Ok(game) = line.parse::<Game>() {
+        assert_eq!(game.to_string().parse::<Game>(), Ok(game));
+    }
+});
@@ -0,0 +1,11 @@
+#![no_main]
+
+use aoc_2023::challenge::day3::{GearRule, SchematicInfo};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    if let Ok(schematic_info) = input.parse::<SchematicInfo>() {
+        _ = schematic_info.get_numbers_adjacent_to_symbol().count();
+        _ = schematic_info.get_gear_ratios(&GearRule::default()).count();
+    }
+});
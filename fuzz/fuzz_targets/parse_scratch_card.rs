@@ -0,0 +1,11 @@
+#![no_main]
+
+use aoc_2023::challenge::day4::ScratchCard;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|line: &str| {
+    if let Ok(scratch_card) = line.parse::<ScratchCard>() {
+        assert!(scratch_card.matching_count() <= scratch_card.own_numbers().len());
+        _ = scratch_card.points();
+    }
+});
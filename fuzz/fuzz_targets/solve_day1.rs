@@ -0,0 +1,10 @@
+#![no_main]
+
+use aoc_2023::challenge::day1;
+use libfuzzer_sys::fuzz_target;
+
+// Solvers can fail on bad input, but must never panic
+fuzz_target!(|input: &str| {
+    _ = day1::run_part_1(input);
+    _ = day1::run_part_2(input);
+});
@@ -0,0 +1,15 @@
+#![no_main]
+
+use aoc_2023::challenge::day3;
+use aoc_2023::challenge::parse::ParseMode;
+use libfuzzer_sys::fuzz_target;
+
+// Solvers can fail on bad input, but must never panic
+fuzz_target!(|input: &str| {
+    for parse_mode in [ParseMode::Strict, ParseMode::Lenient] {
+        let params = day3::Params::default();
+
+        _ = day3::run_part_1(input, parse_mode, &params);
+        _ = day3::run_part_2(input, parse_mode, &params);
+    }
+});
@@ -0,0 +1,23 @@
+#![no_main]
+
+use aoc_2023::challenge::day4;
+use aoc_2023::challenge::day4::OutOfRange;
+use aoc_2023::challenge::parse::ParseMode;
+use libfuzzer_sys::fuzz_target;
+
+// Solvers can fail on bad input, but must never panic
+fuzz_target!(|input: &str| {
+    for parse_mode in [ParseMode::Strict, ParseMode::Lenient] {
+        for out_of_range in [OutOfRange::Clamp, OutOfRange::Error, OutOfRange::Wrap] {
+            for validate in [false, true] {
+                let params = day4::Params {
+                    out_of_range,
+                    validate,
+                };
+
+                _ = day4::run_part_1(input, parse_mode, &params);
+                _ = day4::run_part_2(input, parse_mode, &params);
+            }
+        }
+    }
+});
@@ -0,0 +1,39 @@
+#!/bin/sh
+# Fills corpus/ with seeds taken from the examples in testdata/.
+# Usage: fuzz/seed_corpus.sh [testdata dir]
+set -eu
+
+cd "$(dirname "$0")"
+testdata="${1:-../testdata}"
+
+seed() {
+    mkdir -p "corpus/$1"
+    cat > "corpus/$1/$2"
+}
+
+for in_file in "$testdata"/day*/part*/*/in.txt; do
+    test_dir="$(dirname "$in_file")"
+    test_name="$(basename "$(dirname "$test_dir")")_$(basename "$test_dir")"
+    day="$(basename "$(dirname "$(dirname "$test_dir")")")"
+
+    seed "solve_$day" "$test_name" < "$in_file"
+
+    line_number=0
+    tr -d '\r' < "$in_file" | while IFS= read -r line || [ -n "$line" ]; do
+        line_number=$((line_number + 1))
+
+        case "$day" in
+        day1) printf '%s' "$line" | seed day1_extractors "${test_name}_line$line_number" ;;
+        day2)
+            printf '%s' "$line" | seed parse_game "${test_name}_line$line_number"
+            printf '%s' "${line#*: }" | tr ';' '\n' | sed 's/^ *//' | head -n 1 |
+                seed parse_cube_collection "${test_name}_line$line_number"
+            ;;
+        day4) printf '%s' "$line" | seed parse_scratch_card "${test_name}_line$line_number" ;;
+        esac
+    done
+
+    if [ "$day" = day3 ]; then
+        seed parse_schematic "$test_name" < "$in_file"
+    fi
+done
@@ -1,4 +1,69 @@
+use crate::challenge::num::Overflow;
+use crate::challenge::parse::ParseMode;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use std::fmt::{Display, Formatter};
+
 pub mod day1;
 pub mod day2;
 pub mod day3;
 pub mod day4;
+pub mod grid;
+pub mod num;
+pub mod parse;
+
+/// Parameters for challenges that can be run with something other than the puzzle's rules.
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+pub struct Params {
+    pub parse_mode: ParseMode,
+    pub day2: day2::Params,
+    pub day3: day3::Params,
+    pub day4: day4::Params,
+}
+
+/// Why a challenge could not be solved.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum Error {
+    Parse(String),
+    /// The input parsed, but has no answer
+    Unsolvable(String),
+    Overflow(Overflow),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Parse(err) => write!(f, "parse error: {err}"),
+            Error::Unsolvable(err) => write!(f, "no answer: {err}"),
+            Error::Overflow(err) => write!(f, "overflow: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl<K: Display> From<parse::Error<K>> for Error {
+    fn from(err: parse::Error<K>) -> Self {
+        Error::Parse(err.to_string())
+    }
+}
+
+impl From<Overflow> for Error {
+    fn from(err: Overflow) -> Self {
+        Error::Overflow(err)
+    }
+}
+
+/// Random puzzle input for `day`, always the same for the same `seed` and `size`.
+/// What `size` means depends on the day's `generate`.
+pub fn generate(day: u8, seed: u64, size: usize) -> Option<String> {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+
+    match day {
+        1 => Some(day1::generate(&mut rng, size)),
+        2 => Some(day2::generate(&mut rng, size)),
+        3 => Some(day3::generate(&mut rng, size)),
+        4 => Some(day4::generate(&mut rng, size)),
+        _ => None,
+    }
+}
@@ -1,20 +1,57 @@
-pub fn run_part_1(input: &str) -> String {
+use crate::challenge;
+use crate::challenge::num::Answer;
+use crate::challenge::parse;
+use crate::challenge::parse::Span;
+use itertools::Itertools;
+use rand::seq::IndexedRandom;
+use rand::Rng;
+use std::fmt::{Display, Formatter};
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum ParseError {
+    NoDigits,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseError::NoDigits => write!(f, "line must contain at least one digit"),
+        }
+    }
+}
+
+pub fn run_part_1(input: &str) -> Result<String, challenge::Error> {
+    let calibration_values = calibration_values(input, extract_first_last_ascii_digits)?;
+
+    Ok(Answer::sum(calibration_values)?.to_string())
+}
+
+fn calibration_values(
+    input: &str,
+    extract_digits: fn(&str) -> Option<(u8, u8)>,
+) -> Result<Vec<Answer>, parse::Error<ParseError>> {
     input
         .lines()
-        .map(extract_first_last_ascii_digits)
-        // Sum may not fit into u8, u64 is gonna be reasonable hopefully :)
-        .map(|(digit1, digit2)| (10 * digit1 + digit2) as u64)
-        .sum::<u64>()
-        .to_string()
+        .enumerate()
+        .map(|(line_number, line)| {
+            let (digit1, digit2) = extract_digits(line).ok_or_else(|| parse::Error {
+                line: Some(line_number),
+                ..Span::new(line).error(ParseError::NoDigits)
+            })?;
+
+            // Sum may not fit into u8, so every value is converted before summing
+            Ok(Answer::from((10 * digit1 + digit2) as u64))
+        })
+        .try_collect()
 }
 
-fn extract_first_last_ascii_digits(input: &str) -> (u8, u8) {
+/// `None` if there are no digits in `input`.
+pub fn extract_first_last_ascii_digits(input: &str) -> Option<(u8, u8)> {
     let first_digit = input
         .as_bytes()
         .iter()
         .copied()
-        .find_map(extract_ascii_digit)
-        .expect("No digits found");
+        .find_map(extract_ascii_digit)?;
 
     // Annoyingly, rfind_map doesn't exist
     let last_digit = input
@@ -22,32 +59,28 @@ fn extract_first_last_ascii_digits(input: &str) -> (u8, u8) {
         .iter()
         .rev()
         .copied()
-        .find_map(extract_ascii_digit)
-        .unwrap();
+        .find_map(extract_ascii_digit)?;
 
-    (first_digit, last_digit)
+    Some((first_digit, last_digit))
 }
 
 fn extract_ascii_digit(ascii: u8) -> Option<u8> {
     // Digits '0', '1', etc. are directly next to each other in ascii/UTF-8
-    ascii.is_ascii_digit().then_some(ascii - b'0')
+    ascii.is_ascii_digit().then(|| ascii - b'0')
 }
 
-pub fn run_part_2(input: &str) -> String {
-    input
-        .lines()
-        .map(extract_first_last_ascii_or_spelled_digits)
-        // Sum may not fit into u8, u64 is gonna be reasonable hopefully :)
-        .map(|(digit1, digit2)| (10 * digit1 + digit2) as u64)
-        .sum::<u64>()
-        .to_string()
+pub fn run_part_2(input: &str) -> Result<String, challenge::Error> {
+    let calibration_values = calibration_values(input, extract_first_last_ascii_or_spelled_digits)?;
+
+    Ok(Answer::sum(calibration_values)?.to_string())
 }
 
-fn extract_first_last_ascii_or_spelled_digits(input: &str) -> (u8, u8) {
-    let first_digit = extract_first_ascii_or_spelled_digit(input).expect("No digits found");
-    let last_digit = extract_last_ascii_or_spelled_digit(input).unwrap();
+/// `None` if there are no digits in `input`, neither as ascii nor spelled out.
+pub fn extract_first_last_ascii_or_spelled_digits(input: &str) -> Option<(u8, u8)> {
+    let first_digit = extract_first_ascii_or_spelled_digit(input)?;
+    let last_digit = extract_last_ascii_or_spelled_digit(input)?;
 
-    (first_digit, last_digit)
+    Some((first_digit, last_digit))
 }
 
 fn extract_first_ascii_or_spelled_digit(input: &str) -> Option<u8> {
@@ -102,7 +135,7 @@ fn extract_last_ascii_or_spelled_digit(input: &str) -> Option<u8> {
             // Always remember to add one to index to get the digit since we don't have zero
             let digit = spelled_digit_idx as u8 + 1;
 
-            if char_idx >= spelled.len()
+            if char_idx + 1 >= spelled.len()
                 && input
                     .get((char_idx + 1 - spelled.len())..=char_idx)
                     .is_some_and(|maybe_spelled| maybe_spelled == spelled)
@@ -114,3 +147,131 @@ fn extract_last_ascii_or_spelled_digit(input: &str) -> Option<u8> {
 
     None
 }
+
+/// Calibration document with `size` lines.
+/// Every line has at least one digit, and may spell out more digits for part 2.
+pub fn generate(rng: &mut impl Rng, size: usize) -> String {
+    let spelled_digits = [
+        "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+    ];
+
+    (0..size)
+        .map(|_| {
+            let token_count = rng.random_range(1..=8);
+            let digit_idx = rng.random_range(0..token_count);
+
+            let mut line = String::new();
+
+            for token_idx in 0..token_count {
+                match rng.random_range(0..4) {
+                    _ if token_idx == digit_idx => line.push(rng.random_range('1'..='9')),
+                    0 => line.push(rng.random_range('1'..='9')),
+                    1 => line += spelled_digits.choose(rng).unwrap(),
+                    _ => {
+                        for _ in 0..rng.random_range(1..=4) {
+                            line.push(rng.random_range('a'..='z'));
+                        }
+                    }
+                }
+            }
+
+            line
+        })
+        .join("\n")
+}
+
+#[cfg(test)]
+mod test {
+    use super::ParseError;
+    use crate::challenge;
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+    use proptest::sample::select;
+
+    const SPELLED_DIGITS: [&str; 9] = [
+        "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+    ];
+
+    /// Checks every position for every digit, without any of the shortcuts of the solution.
+    fn reference_calibration_value(line: &str, spelled: bool) -> u64 {
+        let digits: Vec<u64> = (0..line.len())
+            .filter_map(|idx| line.get(idx..))
+            .filter_map(|rest| {
+                let ascii_digit = rest
+                    .chars()
+                    .next()
+                    .and_then(|character| character.to_digit(10));
+                let spelled_digit = SPELLED_DIGITS
+                    .iter()
+                    .position(|spelled_digit| spelled && rest.starts_with(spelled_digit))
+                    .map(|idx| idx as u32 + 1);
+
+                ascii_digit.or(spelled_digit).map(u64::from)
+            })
+            .collect();
+
+        10 * digits[0] + digits[digits.len() - 1]
+    }
+
+    fn reference(input: &str, spelled: bool) -> String {
+        input
+            .lines()
+            .map(|line| reference_calibration_value(line, spelled))
+            .sum::<u64>()
+            .to_string()
+    }
+
+    fn token() -> impl Strategy<Value = String> {
+        prop_oneof![
+            "[a-z]{1,3}",
+            "[ -/]",
+            "[0-9]",
+            select(&SPELLED_DIGITS[..]).prop_map(str::to_string),
+            Just("é".to_string()),
+        ]
+    }
+
+    /// Lines always have a digit, which can only be spelled out for part 2.
+    fn line(spelled: bool) -> impl Strategy<Value = String> {
+        let digit = if spelled {
+            prop_oneof![
+                "[0-9]",
+                select(&SPELLED_DIGITS[..]).prop_map(str::to_string)
+            ]
+            .boxed()
+        } else {
+            "[0-9]".boxed()
+        };
+
+        (vec(token(), 0..6), digit, vec(token(), 0..6))
+            .prop_map(|(before, digit, after)| before.concat() + &digit + &after.concat())
+    }
+
+    fn input(spelled: bool) -> impl Strategy<Value = String> {
+        vec(line(spelled), 1..10).prop_map(|lines| lines.join("\n"))
+    }
+
+    #[test]
+    fn no_digits() {
+        let input = "1abc2\nabc\n3";
+
+        assert!(matches!(
+            super::run_part_1(input),
+            Err(challenge::Error::Parse(err)) if err == format!("line 2, columns 1-3: {}", ParseError::NoDigits)
+        ));
+        assert_eq!(super::run_part_2(input), super::run_part_1(input));
+        assert_eq!(super::run_part_2("one\nabc"), super::run_part_1("1\nabc"));
+    }
+
+    proptest! {
+        #[test]
+        fn part_1_matches_reference(input in input(false)) {
+            prop_assert_eq!(super::run_part_1(&input).unwrap(), reference(&input, false));
+        }
+
+        #[test]
+        fn part_2_matches_reference(input in input(true)) {
+            prop_assert_eq!(super::run_part_2(&input).unwrap(), reference(&input, true));
+        }
+    }
+}
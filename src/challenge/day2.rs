@@ -1,157 +1,635 @@
+use crate::challenge;
+use crate::challenge::num::{Answer, Overflow};
+use crate::challenge::parse;
+use crate::challenge::parse::{ParseMode, Span};
 use itertools::Itertools;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::fmt::{Display, Formatter};
 use std::str::FromStr;
 
 #[derive(Clone, Eq, PartialEq, Debug)]
-struct Game {
+pub struct Params {
+    /// The bag that part 1 checks the games against.
+    /// Part 2 multiplies the minimum cube counts of exactly these colors.
+    pub bag: CubeCollection,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Params {
+            bag: CubeCollection::from_iter([("red", 12), ("green", 13), ("blue", 14)]),
+        }
+    }
+}
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "GameDef")
+)]
+pub struct Game {
     id: u64,
     cube_collections: Vec<CubeCollection>,
 }
 
-#[derive(Copy, Clone, Eq, PartialEq, Debug)]
-struct CubeCollection {
-    red: u64,
-    green: u64,
-    blue: u64,
+/// Unvalidated [`Game`], used so that deserialized games can always be printed as puzzle input.
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct GameDef {
+    id: u64,
+    cube_collections: Vec<CubeCollection>,
+}
+
+/// Amount of cubes per color. Colors that are not present have an amount of zero.
+///
+/// Colors keep the order they were given in, so that printing reproduces the puzzle input,
+/// but two collections with the same amounts in a different order are equal.
+#[derive(Clone, Debug, Default)]
+pub struct CubeCollection {
+    /// Every color appears at most once
+    cubes: Vec<(String, u64)>,
 }
 
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
-enum ParseError {
+pub enum ParseError {
     InvalidGameDef,
     InvalidGameId,
-    UnrecognisedColor,
+    NoCubeCollections,
+    NoCubes,
+    InvalidColor,
     DuplicateColor,
     InvalidCubeAmount,
     NoSpaceInCubeSpec,
 }
 
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let message = match self {
+            ParseError::InvalidGameDef => "game must start with \"Game <id>: \"",
+            ParseError::InvalidGameId => "game id must be an unsigned integer",
+            ParseError::NoCubeCollections => "game must contain at least one cube collection",
+            ParseError::NoCubes => "cube collection must contain at least one color",
+            ParseError::InvalidColor => {
+                "color must be non-empty and not contain whitespace, ',' or ';'"
+            }
+            ParseError::DuplicateColor => "color appears twice in one cube collection",
+            ParseError::InvalidCubeAmount => "cube amount must be an unsigned integer",
+            ParseError::NoSpaceInCubeSpec => "cube amount and color must be separated by a space",
+        };
+
+        write!(f, "{message}")
+    }
+}
+
+impl std::error::Error for ParseError {}
+
 impl FromStr for Game {
-    type Err = ParseError;
+    type Err = parse::Error<ParseError>;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        // Examples:
-        // Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
-        // Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+        Game::parse(Span::new(s), ParseMode::default())
+    }
+}
+
+impl FromStr for CubeCollection {
+    type Err = parse::Error<ParseError>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        CubeCollection::parse(Span::new(s), ParseMode::default())
+    }
+}
+
+fn is_valid_color(color: &str) -> bool {
+    !color.is_empty() && !color.contains(|c: char| c.is_whitespace() || c == ',' || c == ';')
+}
+
+impl Display for Game {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Game {}: {}",
+            self.id,
+            self.cube_collections.iter().join("; ")
+        )
+    }
+}
 
-        let (game_def, cube_collections) = s.split_once(": ").ok_or(ParseError::InvalidGameDef)?;
+impl Display for CubeCollection {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let cubes = self
+            .cubes
+            .iter()
+            .map(|(color, amount)| format!("{amount} {color}"))
+            .join(", ");
 
-        if !game_def.starts_with("Game ") {
-            return Err(ParseError::InvalidGameDef);
+        write!(f, "{cubes}")
+    }
+}
+
+impl PartialEq for CubeCollection {
+    fn eq(&self, other: &Self) -> bool {
+        self.cubes.len() == other.cubes.len()
+            && self
+                .cubes
+                .iter()
+                .all(|(color, amount)| other.get(color) == Some(*amount))
+    }
+}
+
+impl Eq for CubeCollection {}
+
+/// Later amounts of a color replace earlier ones, like for a map.
+impl<'a> FromIterator<(&'a str, u64)> for CubeCollection {
+    fn from_iter<T: IntoIterator<Item = (&'a str, u64)>>(iter: T) -> Self {
+        let mut collection = CubeCollection::default();
+
+        for (color, amount) in iter {
+            collection.insert(color.to_string(), amount);
         }
 
-        let game_id: u64 = game_def["Game ".len()..game_def.len()]
-            .parse()
-            .map_err(|_| ParseError::InvalidGameId)?;
+        collection
+    }
+}
 
-        let cube_collections = cube_collections
-            .split("; ")
-            .map(CubeCollection::from_str)
-            .try_collect()?;
+#[cfg(feature = "serde")]
+impl TryFrom<GameDef> for Game {
+    type Error = ParseError;
+
+    fn try_from(game: GameDef) -> Result<Self, Self::Error> {
+        if game.cube_collections.is_empty() {
+            return Err(ParseError::NoCubeCollections);
+        }
 
         Ok(Game {
-            id: game_id,
-            cube_collections,
+            id: game.id,
+            cube_collections: game.cube_collections,
         })
     }
 }
 
-impl FromStr for CubeCollection {
-    type Err = ParseError;
+/// Serialized as a map from color to amount, in the collection's order.
+#[cfg(feature = "serde")]
+impl serde::Serialize for CubeCollection {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self.cubes.iter().map(|(color, amount)| (color, amount)))
+    }
+}
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        // Examples:
-        // "8 green, 6 blue, 20 red"
-        // "1 blue, 2 green"
-        let mut red = None;
-        let mut green = None;
-        let mut blue = None;
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for CubeCollection {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct CubesVisitor;
 
-        s.split(", ").try_for_each(|part| {
-            let (amount, color) = part.split_once(' ').ok_or(ParseError::NoSpaceInCubeSpec)?;
+        impl<'de> serde::de::Visitor<'de> for CubesVisitor {
+            type Value = CubeCollection;
 
-            let amount: u64 = amount.parse().map_err(|_| ParseError::InvalidCubeAmount)?;
+            fn expecting(&self, f: &mut Formatter) -> std::fmt::Result {
+                write!(f, "a map from color to amount")
+            }
 
-            match color {
-                "red" => {
-                    if red.replace(amount).is_some() {
-                        return Err(ParseError::DuplicateColor);
+            fn visit_map<A: serde::de::MapAccess<'de>>(
+                self,
+                mut map: A,
+            ) -> Result<Self::Value, A::Error> {
+                let mut collection = CubeCollection::default();
+
+                while let Some((color, amount)) = map.next_entry::<String, u64>()? {
+                    if !is_valid_color(&color) {
+                        return Err(serde::de::Error::custom(ParseError::InvalidColor));
                     }
-                }
-                "green" => {
-                    if green.replace(amount).is_some() {
-                        return Err(ParseError::DuplicateColor);
+
+                    if collection.insert(color, amount).is_some() {
+                        return Err(serde::de::Error::custom(ParseError::DuplicateColor));
                     }
                 }
-                "blue" => {
-                    if blue.replace(amount).is_some() {
-                        return Err(ParseError::DuplicateColor);
-                    }
+
+                if collection.cubes.is_empty() {
+                    return Err(serde::de::Error::custom(ParseError::NoCubes));
                 }
-                _ => return Err(ParseError::UnrecognisedColor),
-            };
 
-            Ok(())
+                Ok(collection)
+            }
+        }
+
+        deserializer.deserialize_map(CubesVisitor)
+    }
+}
+
+impl CubeCollection {
+    pub fn parse(s: Span, mode: ParseMode) -> Result<Self, parse::Error<ParseError>> {
+        // Examples:
+        // "8 green, 6 blue, 20 red"
+        // "1 blue, 2 green"
+        let mut collection = CubeCollection::default();
+
+        for (amount, color) in s.pairs(mode, ", ", " ", ParseError::NoSpaceInCubeSpec)? {
+            let amount: u64 = amount.unsigned(ParseError::InvalidCubeAmount)?;
+
+            if !is_valid_color(color.text()) {
+                return Err(color.error(ParseError::InvalidColor));
+            }
+
+            if collection
+                .insert(color.text().to_string(), amount)
+                .is_some()
+            {
+                return Err(color.error(ParseError::DuplicateColor));
+            }
+        }
+
+        Ok(collection)
+    }
+
+    fn get(&self, color: &str) -> Option<u64> {
+        self.cubes
+            .iter()
+            .find(|(other_color, _)| other_color == color)
+            .map(|&(_, amount)| amount)
+    }
+
+    /// Sets the amount of `color`, keeping its position if it is already present.
+    /// Gives the previous amount.
+    fn insert(&mut self, color: String, amount: u64) -> Option<u64> {
+        match self
+            .cubes
+            .iter_mut()
+            .find(|(other_color, _)| *other_color == color)
+        {
+            Some((_, previous_amount)) => Some(std::mem::replace(previous_amount, amount)),
+            None => {
+                self.cubes.push((color, amount));
+                None
+            }
+        }
+    }
+
+    pub fn amount(&self, color: &str) -> u64 {
+        self.get(color).unwrap_or(0)
+    }
+
+    /// Colors in the order they were given in.
+    pub fn colors(&self) -> impl Iterator<Item = &str> + '_ {
+        self.cubes.iter().map(|(color, _)| color.as_str())
+    }
+
+    /// Whether this collection could have been drawn from `bag`.
+    pub fn fits_in(&self, bag: &CubeCollection) -> bool {
+        self.cubes
+            .iter()
+            .all(|&(ref color, amount)| amount <= bag.amount(color))
+    }
+
+    /// Product of the amounts of the given colors.
+    pub fn power<'a>(&self, colors: impl IntoIterator<Item = &'a str>) -> Result<Answer, Overflow> {
+        Answer::product(
+            colors
+                .into_iter()
+                .map(|color| Answer::from(self.amount(color))),
+        )
+    }
+}
+
+impl Game {
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn cube_collections(&self) -> &[CubeCollection] {
+        &self.cube_collections
+    }
+
+    pub fn parse(s: Span, mode: ParseMode) -> Result<Self, parse::Error<ParseError>> {
+        // Examples:
+        // Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+        // Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+
+        let (game_def, cube_collections) = s.split_once(mode, ": ", ParseError::InvalidGameDef)?;
+
+        let game_id: u64 = game_def
+            .keyed(mode, "Game", " ", ParseError::InvalidGameDef)?
+            .unsigned(ParseError::InvalidGameId)?;
+
+        let cube_collections = cube_collections.separated(mode, "; ", |cube_collection| {
+            CubeCollection::parse(cube_collection, mode)
         })?;
 
-        Ok(CubeCollection {
-            red: red.unwrap_or(0),
-            green: green.unwrap_or(0),
-            blue: blue.unwrap_or(0),
+        Ok(Game {
+            id: game_id,
+            cube_collections,
         })
     }
+
+    /// The smallest bag all draws of this game could have been made from.
+    /// Colors are in the order they first appear in.
+    pub fn minimal_bag(&self) -> CubeCollection {
+        let mut minimal_bag = CubeCollection::default();
+
+        for &(ref color, amount) in self.cube_collections.iter().flat_map(|draw| &draw.cubes) {
+            let min_amount = amount.max(minimal_bag.amount(color));
+            minimal_bag.insert(color.clone(), min_amount);
+        }
+
+        minimal_bag
+    }
+
+    /// For every color in the minimal bag, the indices of the draws that force its amount.
+    pub fn forcing_draws(&self) -> Vec<(String, Vec<usize>)> {
+        self.minimal_bag()
+            .cubes
+            .into_iter()
+            .map(|(color, min_amount)| {
+                let draw_idxs = self
+                    .cube_collections
+                    .iter()
+                    .positions(|draw| draw.amount(&color) == min_amount)
+                    .collect();
+
+                (color, draw_idxs)
+            })
+            .collect()
+    }
+
+    /// Indices of the draws that could not have been made from `bag`.
+    pub fn impossible_draws<'a>(
+        &'a self,
+        bag: &'a CubeCollection,
+    ) -> impl Iterator<Item = usize> + 'a {
+        self.cube_collections
+            .iter()
+            .positions(|draw| !draw.fits_in(bag))
+    }
+
+    pub fn is_possible_with(&self, bag: &CubeCollection) -> bool {
+        self.impossible_draws(bag).next().is_none()
+    }
 }
 
-impl CubeCollection {
-    fn is_part_1_possible(&self) -> bool {
-        let max_red = 12;
-        let max_green = 13;
-        let max_blue = 14;
+pub fn parse_games(
+    input: &str,
+    parse_mode: ParseMode,
+) -> Result<Vec<Game>, parse::Error<ParseError>> {
+    parse::parse_lines(input, parse_mode, |line| Game::parse(line, parse_mode))
+}
+
+/// Human readable explanation of part 1 and part 2 for every game.
+pub fn report(
+    input: &str,
+    parse_mode: ParseMode,
+    params: &Params,
+) -> Result<String, challenge::Error> {
+    let games = parse_games(input, parse_mode)?;
+
+    let mut report = format!("Bag: {}\n", params.bag);
+
+    for game in &games {
+        let minimal_bag = game.minimal_bag();
+
+        report += &format!(
+            "\nGame {}\n  minimal bag: {minimal_bag} (power {})\n",
+            game.id,
+            minimal_bag.power(params.bag.colors())?,
+        );
 
-        self.red <= max_red && self.green <= max_green && self.blue <= max_blue
+        for (color, draw_idxs) in game.forcing_draws() {
+            report += &format!("  {color} forced by {}\n", format_draws(&draw_idxs));
+        }
+
+        let impossible_draws = game.impossible_draws(&params.bag).collect_vec();
+
+        if impossible_draws.is_empty() {
+            report += "  possible\n";
+        } else {
+            report += &format!(
+                "  impossible because of {}\n",
+                format_draws(&impossible_draws)
+            );
+        }
     }
+
+    let (possible_games, impossible_games): (Vec<_>, Vec<_>) = games
+        .iter()
+        .partition(|game| game.is_possible_with(&params.bag));
+
+    report += &format!(
+        "\nPossible games: {} (id sum {})\nImpossible games: {}\nPower sum: {}\n",
+        possible_games.iter().map(|game| game.id).join(", "),
+        Answer::sum(possible_games.iter().map(|game| Answer::from(game.id)))?,
+        impossible_games.iter().map(|game| game.id).join(", "),
+        power_sum(&games, params)?,
+    );
+
+    Ok(report)
 }
 
-pub fn run_part_1(input: &str) -> String {
-    input
-        .lines()
-        .map(|line| line.parse::<Game>().expect("Parse error"))
-        .filter(|game| {
-            game.cube_collections
-                .iter()
-                .all(CubeCollection::is_part_1_possible)
+/// Formats draw indices as one-based draw numbers.
+fn format_draws(draw_idxs: &[usize]) -> String {
+    let draw_numbers = draw_idxs.iter().map(|idx| idx + 1).join(", ");
+
+    if draw_idxs.len() == 1 {
+        format!("draw {draw_numbers}")
+    } else {
+        format!("draws {draw_numbers}")
+    }
+}
+
+/// Converts puzzle input into a JSON array of games.
+#[cfg(feature = "serde")]
+pub fn to_json(input: &str, parse_mode: ParseMode) -> Result<String, Box<dyn std::error::Error>> {
+    let games = parse_games(input, parse_mode)?;
+
+    Ok(serde_json::to_string_pretty(&games)?)
+}
+
+/// Converts a JSON array of games into puzzle input.
+#[cfg(feature = "serde")]
+pub fn from_json(json: &str) -> Result<String, Box<dyn std::error::Error>> {
+    let games: Vec<Game> = serde_json::from_str(json)?;
+
+    Ok(games.iter().join("\n"))
+}
+
+fn power_sum(games: &[Game], params: &Params) -> Result<Answer, Overflow> {
+    let powers: Vec<_> = games
+        .iter()
+        .map(|game| game.minimal_bag().power(params.bag.colors()))
+        .try_collect()?;
+
+    Answer::sum(powers)
+}
+
+pub fn run_part_1(
+    input: &str,
+    parse_mode: ParseMode,
+    params: &Params,
+) -> Result<String, challenge::Error> {
+    let games = parse_games(input, parse_mode)?;
+
+    let possible_game_ids = games
+        .into_iter()
+        .filter(|game| game.is_possible_with(&params.bag))
+        .map(|game| Answer::from(game.id));
+
+    Ok(Answer::sum(possible_game_ids)?.to_string())
+}
+
+pub fn run_part_2(
+    input: &str,
+    parse_mode: ParseMode,
+    params: &Params,
+) -> Result<String, challenge::Error> {
+    let games = parse_games(input, parse_mode)?;
+
+    Ok(power_sum(&games, params)?.to_string())
+}
+
+/// `size` games with ids counting up from 1, using red, green and blue cubes.
+pub fn generate(rng: &mut impl Rng, size: usize) -> String {
+    (1..=size)
+        .map(|id| {
+            let mut draws = Vec::new();
+
+            for _ in 0..rng.random_range(1..=6) {
+                let mut colors = ["red", "green", "blue"];
+                colors.shuffle(rng);
+
+                let mut cubes = Vec::new();
+
+                for color in &colors[..rng.random_range(1..=3)] {
+                    cubes.push(format!("{} {color}", rng.random_range(1..=20)));
+                }
+
+                draws.push(cubes.join(", "));
+            }
+
+            format!("Game {id}: {}", draws.join("; "))
         })
-        .map(|game| game.id)
-        .sum::<u64>()
-        .to_string()
-}
-
-pub fn run_part_2(input: &str) -> String {
-    input
-        .lines()
-        .map(|line| line.parse::<Game>().expect("Parse error"))
-        .map(|game| {
-            let min_red = game
-                .cube_collections
-                .iter()
-                .map(|collection| collection.red)
-                .max()
-                .unwrap_or(0);
+        .join("\n")
+}
 
-            let min_green = game
-                .cube_collections
-                .iter()
-                .map(|collection| collection.green)
-                .max()
-                .unwrap_or(0);
+#[cfg(test)]
+mod test {
+    use super::{report, CubeCollection, Game, Params, ParseError};
+    use crate::challenge::parse::{ParseMode, Span};
+    use proptest::collection::{btree_map, vec};
+    use proptest::prelude::*;
 
-            let min_blue = game
-                .cube_collections
-                .iter()
-                .map(|collection| collection.blue)
-                .max()
-                .unwrap_or(0);
+    fn cube_collection() -> impl Strategy<Value = CubeCollection> {
+        btree_map("[a-z]{1,8}", any::<u64>(), 1..5)
+            .prop_map(|cubes| cubes.into_iter().collect::<Vec<_>>())
+            .prop_shuffle()
+            .prop_map(|cubes| CubeCollection { cubes })
+    }
 
-            min_red * min_green * min_blue
+    fn game() -> impl Strategy<Value = Game> {
+        (any::<u64>(), vec(cube_collection(), 1..6)).prop_map(|(id, cube_collections)| Game {
+            id,
+            cube_collections,
         })
-        .sum::<u64>()
-        .to_string()
+    }
+
+    #[test]
+    fn example_report() {
+        let input = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
+
+        let report = report(input, ParseMode::Strict, &Params::default()).unwrap();
+
+        assert!(report.starts_with("Bag: 12 red, 13 green, 14 blue\n"));
+        assert!(report.contains(
+            "Game 1
+  minimal bag: 6 blue, 4 red, 2 green (power 48)
+  blue forced by draw 2
+  red forced by draw 1
+  green forced by draws 2, 3
+  possible
+"
+        ));
+        assert!(report.contains(
+            "Game 3
+  minimal bag: 13 green, 6 blue, 20 red (power 1560)
+  green forced by draw 2
+  blue forced by draw 1
+  red forced by draw 1
+  impossible because of draw 1
+"
+        ));
+        assert!(report.contains("  impossible because of draw 3\n"));
+        assert!(report.ends_with(
+            "Possible games: 1, 2, 5 (id sum 8)
+Impossible games: 3, 4
+Power sum: 2286
+"
+        ));
+    }
+
+    #[test]
+    fn example_round_trip() {
+        let line = "Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red";
+        let game: Game = line.parse().unwrap();
+
+        assert_eq!(game.to_string(), line);
+        assert_eq!(game.to_string().parse::<Game>(), Ok(game));
+    }
+
+    #[test]
+    fn color_order() {
+        let collection: CubeCollection = "8 green, 6 blue, 20 red".parse().unwrap();
+
+        assert_eq!(
+            collection.colors().collect::<Vec<_>>(),
+            vec!["green", "blue", "red"]
+        );
+        assert_eq!(
+            collection,
+            "20 red, 8 green, 6 blue".parse::<CubeCollection>().unwrap()
+        );
+        assert_ne!(
+            collection,
+            "8 green, 6 blue".parse::<CubeCollection>().unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_modes() {
+        let line = "Game  1:\t3 blue ,4 red;1 red\r";
+
+        assert_eq!(
+            Game::parse(Span::new(line), ParseMode::Strict).map_err(|err| err.kind),
+            Err(ParseError::InvalidGameDef)
+        );
+        assert_eq!(
+            Game::parse(Span::new(line), ParseMode::Lenient).map(|game| game.to_string()),
+            Ok("Game 1: 3 blue, 4 red; 1 red".to_string())
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn cube_collection_round_trip(collection in cube_collection()) {
+            prop_assert_eq!(collection.to_string().parse::<CubeCollection>(), Ok(collection));
+        }
+
+        #[test]
+        fn game_round_trip(game in game()) {
+            let printed = game.to_string();
+            let parsed: Game = printed.parse().unwrap();
+
+            prop_assert_eq!(parsed.to_string(), printed);
+            prop_assert_eq!(parsed, game);
+        }
+
+        #[cfg(feature = "serde")]
+        #[test]
+        fn json_round_trip(games in vec(game(), 0..4)) {
+            let input = games.iter().map(Game::to_string).collect::<Vec<_>>().join("\n");
+            let json = super::to_json(&input, ParseMode::Lenient).unwrap();
+
+            prop_assert_eq!(super::from_json(&json).unwrap(), input);
+        }
+    }
 }
@@ -1,148 +1,599 @@
-use std::convert::Infallible;
+use crate::challenge;
+use crate::challenge::grid::{Grid, Neighbourhood, Pos};
+use crate::challenge::num::{Answer, Overflow};
+use crate::challenge::parse;
+use crate::challenge::parse::ParseMode;
+use itertools::Itertools;
+use rand::seq::IndexedRandom;
+use rand::Rng;
+use std::fmt::{Display, Formatter};
+use std::num::NonZeroU32;
 use std::ops::RangeInclusive;
 use std::str::FromStr;
+use strum::{Display, EnumString, EnumVariantNames};
+
+mod explain;
+mod render;
+
+pub use explain::ExplainFormat;
+pub use render::RenderFormat;
+
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+pub struct Params {
+    /// Which cells count as adjacent to each other
+    pub neighbourhood: Neighbourhood,
+    pub gear_rule: GearRule,
+}
+
+/// Which symbols count as gears in part 2, and what their ratio is.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct GearRule {
+    pub symbols: Vec<char>,
+    /// How many numbers have to be adjacent to a symbol for it to be a gear
+    pub adjacent_numbers: RangeInclusive<usize>,
+    /// How the adjacent numbers are combined into the gear ratio
+    pub combination: Combination,
+}
+
+impl Default for GearRule {
+    fn default() -> Self {
+        GearRule {
+            symbols: vec!['*'],
+            adjacent_numbers: 2..=2,
+            combination: Combination::Product,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Display, EnumString, EnumVariantNames)]
+#[strum(serialize_all = "kebab-case")]
+pub enum Combination {
+    Product,
+    Sum,
+    Max,
+}
+
+impl Combination {
+    pub fn combine(self, numbers: impl Iterator<Item = u64>) -> Result<Answer, Overflow> {
+        match self {
+            Combination::Product => Answer::product(numbers.map(Answer::from)),
+            Combination::Sum => Answer::sum(numbers.map(Answer::from)),
+            Combination::Max => Ok(Answer::from(numbers.max().unwrap_or(0))),
+        }
+    }
+}
 
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
-struct SymbolPosition {
-    line: usize,
-    col: usize,
-    is_star: bool,
+pub enum ParseError {
+    RaggedSchematic,
+    NonAsciiCell,
+    NumberTooBig,
+    TooManyNumbers,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseError::RaggedSchematic => {
+                write!(f, "all schematic lines must have the same length")
+            }
+            ParseError::NonAsciiCell => write!(f, "schematic must only contain ASCII characters"),
+            ParseError::NumberTooBig => write!(f, "number must fit into 64 bits"),
+            ParseError::TooManyNumbers => {
+                write!(f, "schematic must have fewer than {} numbers", u32::MAX)
+            }
+        }
+    }
 }
 
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
-struct NumberPosition {
-    number: u64,
-    line: usize,
-    start_col: usize,
+pub struct SymbolPosition {
+    pub pos: Pos,
+    pub symbol: char,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct NumberPosition {
+    pub number: u64,
+    pub line: usize,
+    pub start_col: usize,
     /// Inclusive
-    end_col: usize,
+    pub end_col: usize,
+}
+
+impl NumberPosition {
+    pub fn positions(&self) -> impl Iterator<Item = Pos> {
+        let line = self.line;
+
+        (self.start_col..=self.end_col).map(move |col| Pos::new(line, col))
+    }
 }
 
 #[derive(Clone, Eq, PartialEq, Debug)]
-struct SchematicInfo {
+pub struct SchematicInfo {
+    /// ASCII characters, one byte per cell to keep big schematics small
+    grid: Grid<u8>,
     symbols: Vec<SymbolPosition>,
     numbers: Vec<NumberPosition>,
+    /// For every cell, one more than the index into `numbers` of the number covering it
+    number_idxs: Grid<Option<NonZeroU32>>,
+    neighbourhood: Neighbourhood,
 }
 
-impl FromStr for SchematicInfo {
-    type Err = Infallible;
+impl SchematicInfo {
+    pub fn parse(
+        s: &str,
+        mode: ParseMode,
+        neighbourhood: Neighbourhood,
+    ) -> Result<Self, parse::Error<ParseError>> {
+        let (grid, origins) =
+            parse::grid_with_origins(s, mode, ParseError::RaggedSchematic, |character| {
+                u8::try_from(character)
+                    .ok()
+                    .filter(u8::is_ascii)
+                    .ok_or(ParseError::NonAsciiCell)
+            })?;
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut symbols = Vec::new();
         let mut numbers = Vec::new();
+        let mut number_idxs = grid.map(|_| None);
 
-        for (line_number, line) in s.lines().enumerate() {
-            let mut curr_number_start_col = None;
-            let line_width = line.len();
-
-            for (col, character) in line.bytes().enumerate() {
-                if !character.is_ascii() {
-                    continue;
-                }
+        for (line, row) in grid.rows().enumerate() {
+            let runs = row
+                .iter()
+                .enumerate()
+                .group_by(|(_col, character)| character.is_ascii_digit());
 
-                let is_digit = character.is_ascii_digit();
+            for (_, digits) in runs.into_iter().filter(|(is_digit, _)| *is_digit) {
+                let digits = digits.collect_vec();
+                let start_col = digits[0].0;
+                let end_col = digits[digits.len() - 1].0;
 
-                if is_digit && curr_number_start_col.is_none() {
-                    curr_number_start_col = Some(col);
-                } else if let Some(number_start_col) = curr_number_start_col {
-                    if !is_digit || col + 1 == line_width {
-                        let last_digit_col = if is_digit { line_width - 1 } else { col - 1 };
+                // All cells are ASCII, so columns are also byte offsets into the line
+                let number_error = |kind| origins[line].error(kind, start_col..(end_col + 1));
 
-                        let slice = &line[number_start_col..=last_digit_col];
+                let number: u64 = std::str::from_utf8(&row[start_col..=end_col])
+                    .expect("Digits are ASCII")
+                    .parse()
+                    .map_err(|_| number_error(ParseError::NumberTooBig))?;
 
-                        let number: u64 = slice.parse().unwrap();
+                let number_position = NumberPosition {
+                    number,
+                    line,
+                    start_col,
+                    end_col,
+                };
 
-                        numbers.push(NumberPosition {
-                            number,
-                            line: line_number,
-                            start_col: number_start_col,
-                            end_col: last_digit_col,
-                        });
+                let number_idx = u32::try_from(numbers.len() + 1)
+                    .ok()
+                    .and_then(NonZeroU32::new)
+                    .ok_or_else(|| number_error(ParseError::TooManyNumbers))?;
 
-                        curr_number_start_col = None;
-                    }
+                for pos in number_position.positions() {
+                    number_idxs[pos] = Some(number_idx);
                 }
 
-                if is_symbol(character) {
-                    symbols.push(SymbolPosition {
-                        line: line_number,
-                        col,
-                        is_star: character == b'*',
-                    })
-                }
+                numbers.push(number_position);
             }
         }
 
-        Ok(SchematicInfo { symbols, numbers })
+        let symbols = grid
+            .iter()
+            .map(|(pos, &character)| (pos, char::from(character)))
+            .filter(|&(_pos, character)| is_symbol(character))
+            .map(|(pos, symbol)| SymbolPosition { pos, symbol })
+            .collect();
+
+        Ok(SchematicInfo {
+            grid,
+            symbols,
+            numbers,
+            number_idxs,
+            neighbourhood,
+        })
     }
-}
 
-impl SchematicInfo {
-    fn get_numbers_adjacent_to_symbol(&self) -> impl Iterator<Item = u64> + '_ {
+    pub fn grid(&self) -> &Grid<u8> {
+        &self.grid
+    }
+
+    pub fn symbols(&self) -> &[SymbolPosition] {
+        &self.symbols
+    }
+
+    pub fn numbers(&self) -> &[NumberPosition] {
+        &self.numbers
+    }
+
+    /// Positions of the symbols adjacent to the number, each only once.
+    pub fn adjacent_symbol_positions<'a>(
+        &'a self,
+        number_position: &NumberPosition,
+    ) -> impl Iterator<Item = Pos> + 'a {
+        number_position
+            .positions()
+            .flat_map(|pos| self.grid.neighbourhood(pos, self.neighbourhood))
+            .filter(|&neighbour| is_symbol(char::from(self.grid[neighbour])))
+            .unique()
+    }
+
+    pub fn is_part_number(&self, number_position: &NumberPosition) -> bool {
+        self.adjacent_symbol_positions(number_position)
+            .next()
+            .is_some()
+    }
+
+    pub fn get_numbers_adjacent_to_symbol(&self) -> impl Iterator<Item = u64> + '_ {
         self.numbers
             .iter()
-            .filter(|number_position| {
-                let line_range =
-                    number_position.line.saturating_sub(1)..=number_position.line.saturating_add(1);
-                let col_range = number_position.start_col.saturating_sub(1)
-                    ..=number_position.end_col.saturating_add(1);
-
-                self.symbols.iter().any(|symbol_position| {
-                    line_range.contains(&symbol_position.line)
-                        && col_range.contains(&symbol_position.col)
-                })
-            })
+            .filter(|number_position| self.is_part_number(number_position))
             .map(|number_position| number_position.number)
     }
 
-    fn get_gear_ratios(&self) -> impl Iterator<Item = u64> + '_ {
-        self.symbols.iter().filter_map(|symbol_position| {
-            let line_range =
-                symbol_position.line.saturating_sub(1)..=symbol_position.line.saturating_add(1);
-            let col_range =
-                symbol_position.col.saturating_sub(1)..=symbol_position.col.saturating_add(1);
+    /// Indices into `numbers` of the numbers adjacent to `pos`, each only once.
+    pub fn adjacent_number_idxs(&self, pos: Pos) -> impl Iterator<Item = usize> + '_ {
+        self.grid
+            .neighbourhood(pos, self.neighbourhood)
+            .filter_map(|neighbour| self.number_idxs[neighbour])
+            .map(|number_idx| number_idx.get() as usize - 1)
+            .unique()
+    }
 
-            let (total_adjacent_numbers, product): (usize, u64) = self
-                .numbers
-                .iter()
-                .filter(|number_position| {
-                    line_range.contains(&number_position.line)
-                        && overlaps(
-                            &col_range,
-                            &(number_position.start_col..=number_position.end_col),
-                        )
-                })
-                .fold((0, 1), |(acc_amount, acc_product), next| {
-                    (acc_amount + 1, acc_product * next.number)
-                });
+    /// Symbols that are gears according to `gear_rule`,
+    /// together with the indices into `numbers` of their adjacent numbers.
+    pub fn get_gears<'a>(
+        &'a self,
+        gear_rule: &'a GearRule,
+    ) -> impl Iterator<Item = (&'a SymbolPosition, Vec<usize>)> + 'a {
+        self.symbols
+            .iter()
+            .filter(|symbol_position| gear_rule.symbols.contains(&symbol_position.symbol))
+            .filter_map(|symbol_position| {
+                let adjacent_number_idxs =
+                    self.adjacent_number_idxs(symbol_position.pos).collect_vec();
 
-            (total_adjacent_numbers == 2).then_some(product)
-        })
+                gear_rule
+                    .adjacent_numbers
+                    .contains(&adjacent_number_idxs.len())
+                    .then_some((symbol_position, adjacent_number_idxs))
+            })
+    }
+
+    pub fn get_gear_ratios<'a>(
+        &'a self,
+        gear_rule: &'a GearRule,
+    ) -> impl Iterator<Item = Result<Answer, Overflow>> + 'a {
+        self.get_gears(gear_rule)
+            .map(|(_symbol_position, adjacent_number_idxs)| {
+                gear_rule.combination.combine(
+                    adjacent_number_idxs
+                        .into_iter()
+                        .map(|idx| self.numbers[idx].number),
+                )
+            })
     }
 }
 
-fn is_symbol(ascii: u8) -> bool {
-    ascii.is_ascii() && ascii != b'.' && !ascii.is_ascii_control() && !ascii.is_ascii_alphanumeric()
+impl FromStr for SchematicInfo {
+    type Err = parse::Error<ParseError>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        SchematicInfo::parse(s, ParseMode::default(), Neighbourhood::default())
+    }
 }
 
-fn overlaps(a: &RangeInclusive<usize>, b: &RangeInclusive<usize>) -> bool {
-    a.contains(b.start()) || a.contains(b.end()) || b.contains(a.start())
+/// Whether `character` is a symbol in a schematic, i.e. not a digit or a `.`.
+pub fn is_symbol(character: char) -> bool {
+    character.is_ascii()
+        && character != '.'
+        && !character.is_ascii_control()
+        && !character.is_ascii_alphanumeric()
 }
 
-pub fn run_part_1(input: &str) -> String {
-    input
-        .parse::<SchematicInfo>()
-        .unwrap()
+pub fn run_part_1(
+    input: &str,
+    parse_mode: ParseMode,
+    params: &Params,
+) -> Result<String, challenge::Error> {
+    let schematic_info = SchematicInfo::parse(input, parse_mode, params.neighbourhood)?;
+
+    let part_numbers = schematic_info
         .get_numbers_adjacent_to_symbol()
-        .sum::<u64>()
-        .to_string()
+        .map(Answer::from);
+
+    Ok(Answer::sum(part_numbers)?.to_string())
+}
+
+pub fn run_part_2(
+    input: &str,
+    parse_mode: ParseMode,
+    params: &Params,
+) -> Result<String, challenge::Error> {
+    let schematic_info = SchematicInfo::parse(input, parse_mode, params.neighbourhood)?;
+
+    let gear_ratios: Vec<_> = schematic_info
+        .get_gear_ratios(&params.gear_rule)
+        .try_collect()?;
+
+    Ok(Answer::sum(gear_ratios)?.to_string())
 }
 
-pub fn run_part_2(input: &str) -> String {
-    input
-        .parse::<SchematicInfo>()
-        .unwrap()
-        .get_gear_ratios()
-        .sum::<u64>()
-        .to_string()
+/// Draws the schematic with part numbers, other numbers and gears highlighted.
+pub fn render(
+    input: &str,
+    parse_mode: ParseMode,
+    params: &Params,
+    format: RenderFormat,
+) -> Result<String, parse::Error<ParseError>> {
+    let schematic_info = SchematicInfo::parse(input, parse_mode, params.neighbourhood)?;
+
+    Ok(render::render(&schematic_info, &params.gear_rule, format))
+}
+
+/// Lists every number with the symbols that make it a part number, and every gear with its ratio.
+pub fn explain(
+    input: &str,
+    parse_mode: ParseMode,
+    params: &Params,
+    format: ExplainFormat,
+) -> Result<String, challenge::Error> {
+    let schematic_info = SchematicInfo::parse(input, parse_mode, params.neighbourhood)?;
+
+    Ok(explain::explain(
+        &schematic_info,
+        &params.gear_rule,
+        format,
+    )?)
+}
+
+/// Square schematic with `size` lines of `size` cells.
+/// Numbers have up to three digits like in the puzzle, so part 2 answers stay small.
+pub fn generate(rng: &mut impl Rng, size: usize) -> String {
+    let symbols = ['*', '#', '+', '$', '/', '@', '=', '%', '-', '&'];
+
+    (0..size)
+        .map(|_| {
+            let mut line = String::with_capacity(size);
+
+            while line.len() < size {
+                match rng.random_range(0..10) {
+                    0 | 1 => {
+                        let digit_count = rng.random_range(1..=(size - line.len()).min(3)) as u32;
+                        line += &rng
+                            .random_range(10u32.pow(digit_count - 1)..10u32.pow(digit_count))
+                            .to_string();
+
+                        // Numbers next to each other would merge into one
+                        if line.len() < size {
+                            line.push('.');
+                        }
+                    }
+                    2 => line.push(*symbols.choose(rng).unwrap()),
+                    _ => line.push('.'),
+                }
+            }
+
+            line
+        })
+        .join("\n")
+}
+
+#[cfg(test)]
+mod test {
+    use super::{is_symbol, Combination, GearRule, Params, ParseError, SchematicInfo};
+    use crate::challenge::parse::ParseMode;
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+    use std::ops::Range;
+
+    /// Line, first and last column of every number.
+    fn reference_numbers(rows: &[Vec<char>]) -> Vec<(u64, usize, usize, usize)> {
+        let mut numbers = Vec::new();
+
+        for (line, row) in rows.iter().enumerate() {
+            let mut col = 0;
+
+            while col < row.len() {
+                if row[col].is_ascii_digit() {
+                    let start_col = col;
+
+                    while col < row.len() && row[col].is_ascii_digit() {
+                        col += 1;
+                    }
+
+                    let number = row[start_col..col].iter().collect::<String>();
+                    numbers.push((number.parse().unwrap(), line, start_col, col - 1));
+                } else {
+                    col += 1;
+                }
+            }
+        }
+
+        numbers
+    }
+
+    /// Whether `(line, col)` is in the box around the number, diagonals included.
+    fn touches(number: (u64, usize, usize, usize), line: usize, col: usize) -> bool {
+        let (_, number_line, start_col, end_col) = number;
+
+        line + 1 >= number_line
+            && line <= number_line + 1
+            && col + 1 >= start_col
+            && col <= end_col + 1
+    }
+
+    /// Scans the whole schematic for every number and every symbol.
+    fn reference_part_1(rows: &[Vec<char>]) -> u64 {
+        reference_numbers(rows)
+            .into_iter()
+            .filter(|&number| {
+                rows.iter().enumerate().any(|(line, row)| {
+                    row.iter()
+                        .enumerate()
+                        .any(|(col, &cell)| is_symbol(cell) && touches(number, line, col))
+                })
+            })
+            .map(|(number, ..)| number)
+            .sum()
+    }
+
+    fn reference_part_2(rows: &[Vec<char>]) -> u64 {
+        let numbers = reference_numbers(rows);
+        let mut sum = 0;
+
+        for (line, row) in rows.iter().enumerate() {
+            for (col, &cell) in row.iter().enumerate() {
+                let adjacent = numbers
+                    .iter()
+                    .filter(|&&number| touches(number, line, col))
+                    .collect::<Vec<_>>();
+
+                if cell == '*' && adjacent.len() == 2 {
+                    sum += adjacent[0].0 * adjacent[1].0;
+                }
+            }
+        }
+
+        sum
+    }
+
+    /// At most 8 columns, so that gear ratios fit into a u64.
+    fn schematic() -> impl Strategy<Value = Vec<Vec<char>>> {
+        let cell = prop_oneof![
+            4 => Just('.'),
+            3 => proptest::char::range('0', '9'),
+            1 => proptest::sample::select(vec!['*', '*', '#', '+', '-']),
+        ];
+
+        (1..=8usize, 1..=8usize)
+            .prop_flat_map(move |(width, height)| vec(vec(cell.clone(), width), height))
+    }
+
+    fn input(rows: &[Vec<char>]) -> String {
+        rows.iter()
+            .map(|row| row.iter().collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    const EXAMPLE: &str = "467..114..
+...*......
+..35..633.
+......#...
+617*......
+.....+.58.
+..592.....
+......755.
+...$.*....
+.664.598..";
+
+    /// Part 2 answer with `gear_rule` and the default neighbourhood.
+    fn part_2(input: &str, gear_rule: GearRule) -> String {
+        let params = Params {
+            gear_rule,
+            ..Params::default()
+        };
+
+        super::run_part_2(input, ParseMode::Strict, &params).unwrap()
+    }
+
+    #[test]
+    fn gear_rules() {
+        let gear_rule = |symbols: &str, adjacent_numbers, combination| GearRule {
+            symbols: symbols.chars().collect(),
+            adjacent_numbers,
+            combination,
+        };
+
+        assert_eq!(part_2(EXAMPLE, GearRule::default()), "467835");
+        assert_eq!(
+            part_2(EXAMPLE, gear_rule("*", 2..=2, Combination::Sum)),
+            (467 + 35 + 755 + 598).to_string()
+        );
+        assert_eq!(
+            part_2(EXAMPLE, gear_rule("*", 2..=2, Combination::Max)),
+            (467 + 755).to_string()
+        );
+        // The $ and one of the * are only next to one number
+        assert_eq!(
+            part_2(EXAMPLE, gear_rule("*$", 1..=2, Combination::Product)),
+            (467 * 35 + 617 + 664 + 755 * 598).to_string()
+        );
+
+        // The * is next to four numbers, the # to three
+        let crowded = "7.2.3\n.*.#.\n4.5..";
+
+        assert_eq!(part_2(crowded, GearRule::default()), "0");
+        assert_eq!(
+            part_2(crowded, gear_rule("*", 2..=4, Combination::Product)),
+            (7 * 2 * 4 * 5).to_string()
+        );
+        assert_eq!(
+            part_2(crowded, gear_rule("*#", 2..=4, Combination::Product)),
+            (7 * 2 * 4 * 5 + 2 * 3 * 5).to_string()
+        );
+        assert_eq!(
+            part_2(crowded, gear_rule("*#", 2..=3, Combination::Sum)),
+            (2 + 3 + 5).to_string()
+        );
+        assert_eq!(
+            part_2(crowded, gear_rule("*#", 2..=4, Combination::Max)),
+            (7 + 5).to_string()
+        );
+    }
+
+    fn parse_error(input: &str) -> Option<(ParseError, Option<usize>, Range<usize>)> {
+        input
+            .parse::<SchematicInfo>()
+            .err()
+            .map(|err| (err.kind, err.line, err.span))
+    }
+
+    #[test]
+    fn number_too_big() {
+        assert_eq!(
+            parse_error("......................\n*.99999999999999999999"),
+            Some((ParseError::NumberTooBig, Some(1), 2..22))
+        );
+        // Skipped blank lines and indentation still count towards the position in the input
+        assert_eq!(
+            parse_error("\n\n......................\n  *.99999999999999999999"),
+            Some((ParseError::NumberTooBig, Some(3), 4..24))
+        );
+    }
+
+    #[test]
+    fn non_ascii_cell() {
+        assert_eq!(
+            parse_error("1.\né."),
+            Some((ParseError::NonAsciiCell, Some(1), 0..2))
+        );
+    }
+
+    /// Takes about 20 seconds and 1.2 GB in release builds,
+    /// run with `cargo test --release -- --ignored big_schematic`.
+    #[test]
+    #[ignore]
+    fn big_schematic() {
+        let input = crate::challenge::generate(3, 0, 10_000).unwrap();
+        let params = Params::default();
+
+        assert!(super::run_part_1(&input, ParseMode::Strict, &params).is_ok());
+        assert!(super::run_part_2(&input, ParseMode::Strict, &params).is_ok());
+    }
+
+    proptest! {
+        #[test]
+        fn part_1_matches_reference(rows in schematic()) {
+            let answer = super::run_part_1(&input(&rows), ParseMode::Strict, &Params::default());
+
+            prop_assert_eq!(answer.unwrap(), reference_part_1(&rows).to_string());
+        }
+
+        #[test]
+        fn part_2_matches_reference(rows in schematic()) {
+            let answer = super::run_part_2(&input(&rows), ParseMode::Strict, &Params::default());
+
+            prop_assert_eq!(answer.unwrap(), reference_part_2(&rows).to_string());
+        }
+    }
 }
@@ -0,0 +1,231 @@
+use super::{GearRule, SchematicInfo};
+use crate::challenge::grid::Pos;
+use crate::challenge::num::{Answer, Overflow};
+use itertools::Itertools;
+use strum::{Display, EnumString, EnumVariantNames};
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default, Display, EnumString, EnumVariantNames)]
+#[strum(serialize_all = "kebab-case")]
+pub enum ExplainFormat {
+    #[default]
+    Text,
+    #[cfg(feature = "serde")]
+    Json,
+}
+
+/// Positions are one-based, like in editors.
+#[derive(Clone, Eq, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+struct Explanation {
+    numbers: Vec<NumberExplanation>,
+    gears: Vec<GearExplanation>,
+    part_number_sum: Answer,
+    gear_ratio_sum: Answer,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+struct NumberExplanation {
+    number: u64,
+    line: usize,
+    start_col: usize,
+    end_col: usize,
+    /// Empty if this is not a part number
+    adjacent_symbols: Vec<SymbolExplanation>,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+struct SymbolExplanation {
+    symbol: char,
+    line: usize,
+    col: usize,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+struct GearExplanation {
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    symbol: SymbolExplanation,
+    numbers: Vec<u64>,
+    ratio: Answer,
+}
+
+impl SymbolExplanation {
+    fn new(schematic_info: &SchematicInfo, pos: Pos) -> Self {
+        SymbolExplanation {
+            symbol: char::from(schematic_info.grid[pos]),
+            line: pos.line + 1,
+            col: pos.col + 1,
+        }
+    }
+}
+
+fn explanation(
+    schematic_info: &SchematicInfo,
+    gear_rule: &GearRule,
+) -> Result<Explanation, Overflow> {
+    let numbers = schematic_info
+        .numbers
+        .iter()
+        .map(|number_position| NumberExplanation {
+            number: number_position.number,
+            line: number_position.line + 1,
+            start_col: number_position.start_col + 1,
+            end_col: number_position.end_col + 1,
+            adjacent_symbols: schematic_info
+                .adjacent_symbol_positions(number_position)
+                .map(|pos| SymbolExplanation::new(schematic_info, pos))
+                .collect(),
+        })
+        .collect_vec();
+
+    let gears = schematic_info
+        .get_gears(gear_rule)
+        .map(|(symbol_position, adjacent_number_idxs)| {
+            let numbers = adjacent_number_idxs
+                .into_iter()
+                .map(|idx| schematic_info.numbers[idx].number)
+                .collect_vec();
+
+            Ok(GearExplanation {
+                symbol: SymbolExplanation::new(schematic_info, symbol_position.pos),
+                ratio: gear_rule.combination.combine(numbers.iter().copied())?,
+                numbers,
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(Explanation {
+        part_number_sum: Answer::sum(
+            numbers
+                .iter()
+                .filter(|number| !number.adjacent_symbols.is_empty())
+                .map(|number| Answer::from(number.number)),
+        )?,
+        gear_ratio_sum: Answer::sum(gears.iter().map(|gear| gear.ratio.clone()))?,
+        numbers,
+        gears,
+    })
+}
+
+pub(super) fn explain(
+    schematic_info: &SchematicInfo,
+    gear_rule: &GearRule,
+    format: ExplainFormat,
+) -> Result<String, Overflow> {
+    let explanation = explanation(schematic_info, gear_rule)?;
+
+    Ok(match format {
+        ExplainFormat::Text => explanation_text(&explanation, gear_rule),
+        #[cfg(feature = "serde")]
+        ExplainFormat::Json => {
+            serde_json::to_string_pretty(&explanation).expect("Explanation is always valid JSON")
+        }
+    })
+}
+
+fn explanation_text(explanation: &Explanation, gear_rule: &GearRule) -> String {
+    let mut text = String::from("Numbers:\n");
+
+    for number in &explanation.numbers {
+        let symbols = if number.adjacent_symbols.is_empty() {
+            "none".to_string()
+        } else {
+            number
+                .adjacent_symbols
+                .iter()
+                .map(|symbol| {
+                    format!(
+                        "{} at line {}, column {}",
+                        symbol.symbol, symbol.line, symbol.col
+                    )
+                })
+                .join("; ")
+        };
+
+        text += &format!(
+            "  {} at line {}, columns {}-{}: {symbols}\n",
+            number.number, number.line, number.start_col, number.end_col
+        );
+    }
+
+    text += "\nGears:\n";
+
+    for gear in &explanation.gears {
+        text += &format!(
+            "  {} at line {}, column {}: {} ({} {})\n",
+            gear.symbol.symbol,
+            gear.symbol.line,
+            gear.symbol.col,
+            gear.numbers.iter().join(", "),
+            gear_rule.combination,
+            gear.ratio
+        );
+    }
+
+    text += &format!(
+        "\nPart number sum: {}\nGear ratio sum: {}\n",
+        explanation.part_number_sum, explanation.gear_ratio_sum
+    );
+
+    text
+}
+
+#[cfg(test)]
+mod test {
+    use super::ExplainFormat;
+    use crate::challenge::day3::{explain, Params};
+    use crate::challenge::parse::ParseMode;
+
+    /// A gear between two part numbers, a number next to two symbols and one next to none.
+    const INPUT: &str = "12*3\n#...\n..45";
+
+    fn explain_input(format: ExplainFormat) -> String {
+        explain(INPUT, ParseMode::Strict, &Params::default(), format).unwrap()
+    }
+
+    #[test]
+    fn text() {
+        assert_eq!(
+            explain_input(ExplainFormat::Text),
+            "Numbers:
+  12 at line 1, columns 1-2: # at line 2, column 1; * at line 1, column 3
+  3 at line 1, columns 4-4: * at line 1, column 3
+  45 at line 3, columns 3-4: none
+
+Gears:
+  * at line 1, column 3: 12, 3 (product 36)
+
+Part number sum: 15
+Gear ratio sum: 36
+"
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn json() {
+        let json: serde_json::Value =
+            serde_json::from_str(&explain_input(ExplainFormat::Json)).unwrap();
+
+        let star = serde_json::json!({ "symbol": "*", "line": 1, "col": 3 });
+        let hash = serde_json::json!({ "symbol": "#", "line": 2, "col": 1 });
+
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "numbers": [
+                    { "number": 12, "line": 1, "start_col": 1, "end_col": 2, "adjacent_symbols": [hash, star] },
+                    { "number": 3, "line": 1, "start_col": 4, "end_col": 4, "adjacent_symbols": [star] },
+                    { "number": 45, "line": 3, "start_col": 3, "end_col": 4, "adjacent_symbols": [] },
+                ],
+                "gears": [
+                    { "symbol": "*", "line": 1, "col": 3, "numbers": [12, 3], "ratio": 36 },
+                ],
+                "part_number_sum": 15,
+                "gear_ratio_sum": 36,
+            })
+        );
+    }
+}
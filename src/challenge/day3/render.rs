@@ -0,0 +1,178 @@
+use super::{is_symbol, GearRule, SchematicInfo};
+use crate::challenge::grid::Grid;
+use itertools::Itertools;
+use strum::{Display, EnumString, EnumVariantNames};
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default, Display, EnumString, EnumVariantNames)]
+#[strum(serialize_all = "kebab-case")]
+pub enum RenderFormat {
+    /// Colored with ANSI escape codes, for the terminal
+    #[default]
+    Ansi,
+    /// Standalone HTML page
+    Html,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+enum CellKind {
+    Empty,
+    PartNumber,
+    OtherNumber,
+    Gear,
+    Symbol,
+}
+
+impl CellKind {
+    fn ansi_color(self) -> &'static str {
+        match self {
+            CellKind::Empty => "\x1b[2m",
+            CellKind::PartNumber => "\x1b[32m",
+            CellKind::OtherNumber => "\x1b[31m",
+            CellKind::Gear => "\x1b[1;33m",
+            CellKind::Symbol => "\x1b[36m",
+        }
+    }
+
+    fn html_class(self) -> &'static str {
+        match self {
+            CellKind::Empty => "empty",
+            CellKind::PartNumber => "part-number",
+            CellKind::OtherNumber => "other-number",
+            CellKind::Gear => "gear",
+            CellKind::Symbol => "symbol",
+        }
+    }
+}
+
+const ANSI_RESET: &str = "\x1b[0m";
+
+const HTML_STYLE: &str = "\
+body { background: #0f0f23; color: #cccccc; font-family: monospace; }
+.empty { color: #444444; }
+.part-number { color: #00cc00; }
+.other-number { color: #ff4444; }
+.gear { color: #ffff66; font-weight: bold; }
+.symbol { color: #66ccff; }";
+
+fn cell_kinds(schematic_info: &SchematicInfo, gear_rule: &GearRule) -> Grid<CellKind> {
+    let mut cell_kinds = schematic_info.grid.map(|&character| {
+        if is_symbol(char::from(character)) {
+            CellKind::Symbol
+        } else {
+            CellKind::Empty
+        }
+    });
+
+    for number_position in &schematic_info.numbers {
+        let kind = if schematic_info.is_part_number(number_position) {
+            CellKind::PartNumber
+        } else {
+            CellKind::OtherNumber
+        };
+
+        for pos in number_position.positions() {
+            cell_kinds[pos] = kind;
+        }
+    }
+
+    for (symbol_position, _adjacent_number_idxs) in schematic_info.get_gears(gear_rule) {
+        cell_kinds[symbol_position.pos] = CellKind::Gear;
+    }
+
+    cell_kinds
+}
+
+pub(super) fn render(
+    schematic_info: &SchematicInfo,
+    gear_rule: &GearRule,
+    format: RenderFormat,
+) -> String {
+    let cell_kinds = cell_kinds(schematic_info, gear_rule);
+
+    let lines = schematic_info
+        .grid
+        .rows()
+        .zip(cell_kinds.rows())
+        .map(|(row, kinds)| {
+            // Only switch colors between runs of cells of the same kind
+            let runs = row.iter().zip(kinds).group_by(|(_character, &kind)| kind);
+
+            runs.into_iter()
+                .map(|(kind, cells)| {
+                    let text: String = cells
+                        .map(|(&character, _kind)| char::from(character))
+                        .collect();
+
+                    match format {
+                        RenderFormat::Ansi => format!("{}{text}{ANSI_RESET}", kind.ansi_color()),
+                        RenderFormat::Html => format!(
+                            "<span class=\"{}\">{}</span>",
+                            kind.html_class(),
+                            escape_html(&text)
+                        ),
+                    }
+                })
+                .join("")
+        })
+        .join("\n");
+
+    match format {
+        RenderFormat::Ansi => format!("{lines}\n"),
+        RenderFormat::Html => format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+             <title>Schematic</title>\n<style>\n{HTML_STYLE}\n</style>\n</head>\n<body>\n\
+             <p><span class=\"part-number\">part number</span> \
+             <span class=\"other-number\">other number</span> \
+             <span class=\"gear\">gear</span> \
+             <span class=\"symbol\">symbol</span></p>\n\
+             <pre>\n{lines}\n</pre>\n</body>\n</html>\n"
+        ),
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod test {
+    use super::RenderFormat;
+    use crate::challenge::day3::{render, Params};
+    use crate::challenge::parse::ParseMode;
+
+    /// A gear between two part numbers, symbols that need escaping in HTML and another number.
+    const INPUT: &str = "12*3\n&<..\n....\n..45";
+
+    fn render_input(format: RenderFormat) -> String {
+        render(INPUT, ParseMode::Strict, &Params::default(), format).unwrap()
+    }
+
+    #[test]
+    fn ansi() {
+        assert_eq!(
+            render_input(RenderFormat::Ansi),
+            "\x1b[32m12\x1b[0m\x1b[1;33m*\x1b[0m\x1b[32m3\x1b[0m\n\
+             \x1b[36m&<\x1b[0m\x1b[2m..\x1b[0m\n\
+             \x1b[2m....\x1b[0m\n\
+             \x1b[2m..\x1b[0m\x1b[31m45\x1b[0m\n"
+        );
+    }
+
+    #[test]
+    fn html() {
+        let html = render_input(RenderFormat::Html);
+
+        assert!(html.starts_with("<!DOCTYPE html>\n"));
+        assert!(html.contains(
+            "<pre>\n\
+             <span class=\"part-number\">12</span><span class=\"gear\">*</span>\
+             <span class=\"part-number\">3</span>\n\
+             <span class=\"symbol\">&amp;&lt;</span><span class=\"empty\">..</span>\n\
+             <span class=\"empty\">....</span>\n\
+             <span class=\"empty\">..</span><span class=\"other-number\">45</span>\n\
+             </pre>\n"
+        ));
+    }
+}
@@ -1,107 +1,428 @@
+use crate::challenge;
+use crate::challenge::num::{Answer, Overflow};
+use crate::challenge::parse;
+use crate::challenge::parse::{ParseMode, Span};
 use itertools::Itertools;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
 use std::str::FromStr;
 
+mod cascade;
+
+pub use cascade::{puzzle_rule, Cascade, CascadeError, CascadeFormat, OutOfRange};
+
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+pub struct Params {
+    /// What happens when a card wins copies of cards past the end of the table
+    pub out_of_range: OutOfRange,
+    /// Reject cards whose ids don't count up from 1,
+    /// or that list a number twice in their own or winning numbers
+    pub validate: bool,
+}
+
+#[allow(clippy::enum_variant_names)]
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
-enum ParseError {
+pub enum ParseError {
     InvalidScratchCardDef,
     InvalidNumbersSection,
     InvalidOwnNumber,
     InvalidWinningNumber,
+    NonSequentialCardId { expected: u64, actual: u64 },
+    DuplicateOwnNumber(u64),
+    DuplicateWinningNumber(u64),
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseError::InvalidScratchCardDef => {
+                write!(f, "scratch card must start with \"Card <id>: \"")
+            }
+            ParseError::InvalidNumbersSection => {
+                write!(f, "own and winning numbers must be separated by \" | \"")
+            }
+            ParseError::InvalidOwnNumber => write!(f, "own number must be an unsigned integer"),
+            ParseError::InvalidWinningNumber => {
+                write!(f, "winning number must be an unsigned integer")
+            }
+            ParseError::NonSequentialCardId { expected, actual } => write!(
+                f,
+                "card ids must be sequential starting at 1, expected card {expected} but found card {actual}"
+            ),
+            ParseError::DuplicateOwnNumber(number) => {
+                write!(f, "own number {number} appears more than once")
+            }
+            ParseError::DuplicateWinningNumber(number) => {
+                write!(f, "winning number {number} appears more than once")
+            }
+        }
+    }
 }
 
 #[derive(Clone, Eq, PartialEq, Debug)]
-struct ScratchCard {
-    own_numbers: Vec<u64>,
-    winning_numbers: Vec<u64>,
+pub struct ScratchCard {
+    id: u64,
+    own_numbers: HashSet<u64>,
+    winning_numbers: HashSet<u64>,
 }
 
 impl ScratchCard {
-    fn points(&self) -> u64 {
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn own_numbers(&self) -> &HashSet<u64> {
+        &self.own_numbers
+    }
+
+    pub fn winning_numbers(&self) -> &HashSet<u64> {
+        &self.winning_numbers
+    }
+
+    pub fn points(&self) -> Result<Answer, Overflow> {
         let matching_count = self.matching_count();
 
         if matching_count == 0 {
-            0
+            Ok(Answer::zero())
         } else {
-            2u64.pow(matching_count as u32 - 1)
+            Answer::pow2(matching_count - 1)
         }
     }
 
-    fn matching_count(&self) -> usize {
-        self.own_numbers
-            .iter()
-            .filter(|num| self.winning_numbers.contains(num))
-            .count()
+    /// How many different own numbers are winning numbers.
+    pub fn matching_count(&self) -> usize {
+        self.own_numbers.intersection(&self.winning_numbers).count()
     }
 }
 
 impl FromStr for ScratchCard {
-    type Err = ParseError;
+    type Err = parse::Error<ParseError>;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ScratchCard::parse(Span::new(s), ParseMode::default(), &Params::default())
+    }
+}
+
+impl ScratchCard {
+    pub fn parse(
+        s: Span,
+        mode: ParseMode,
+        params: &Params,
+    ) -> Result<Self, parse::Error<ParseError>> {
         // Examples:
         // Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
         // Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
 
-        let (card_def, all_numbers) = s
-            .split_once(": ")
-            .ok_or(ParseError::InvalidScratchCardDef)?;
-        let (own_numbers, winning_numbers) = all_numbers
-            .split_once(" | ")
-            .ok_or(ParseError::InvalidNumbersSection)?;
+        let (card_def, all_numbers) =
+            s.split_once(mode, ": ", ParseError::InvalidScratchCardDef)?;
+        let (own_numbers, winning_numbers) =
+            all_numbers.split_once(mode, " | ", ParseError::InvalidNumbersSection)?;
 
-        if !card_def.starts_with("Card ") {
-            return Err(ParseError::InvalidScratchCardDef);
-        }
+        let id = card_def
+            .keyed(mode, "Card", " ", ParseError::InvalidScratchCardDef)?
+            .trim_start()
+            .unsigned(ParseError::InvalidScratchCardDef)?;
 
-        let own_numbers = own_numbers
-            .split(' ')
-            .filter(|s| !s.is_empty())
-            .map(|s| s.parse::<u64>().map_err(|_| ParseError::InvalidOwnNumber))
-            .try_collect()?;
-
-        let winning_numbers = winning_numbers
-            .split(' ')
-            .filter(|s| !s.is_empty())
-            .map(|s| {
-                s.parse::<u64>()
-                    .map_err(|_| ParseError::InvalidWinningNumber)
-            })
-            .try_collect()?;
+        let own_numbers = parse_numbers(
+            own_numbers,
+            mode,
+            params,
+            ParseError::InvalidOwnNumber,
+            ParseError::DuplicateOwnNumber,
+        )?;
+
+        let winning_numbers = parse_numbers(
+            winning_numbers,
+            mode,
+            params,
+            ParseError::InvalidWinningNumber,
+            ParseError::DuplicateWinningNumber,
+        )?;
 
         Ok(ScratchCard {
+            id,
             own_numbers,
             winning_numbers,
         })
     }
 }
 
-pub fn run_part_1(input: &str) -> String {
-    input
-        .lines()
-        .map(|line| line.parse::<ScratchCard>().expect("Parse error"))
-        .map(|scratch_card| scratch_card.points())
-        .sum::<u64>()
-        .to_string()
+/// Duplicate numbers are only rejected if `params` asks for validation.
+fn parse_numbers(
+    s: Span,
+    mode: ParseMode,
+    params: &Params,
+    kind: ParseError,
+    duplicate_kind: fn(u64) -> ParseError,
+) -> Result<HashSet<u64>, parse::Error<ParseError>> {
+    let mut numbers = HashSet::new();
+
+    for number_span in s.split(mode, " ").filter(|s| !s.text().is_empty()) {
+        let number = number_span.unsigned(kind)?;
+
+        if !numbers.insert(number) && params.validate {
+            return Err(number_span.error(duplicate_kind(number)));
+        }
+    }
+
+    Ok(numbers)
+}
+
+pub fn run_part_1(
+    input: &str,
+    parse_mode: ParseMode,
+    params: &Params,
+) -> Result<String, challenge::Error> {
+    let points: Vec<_> = parse_scratch_cards(input, parse_mode, params)?
+        .iter()
+        .map(ScratchCard::points)
+        .try_collect()?;
+
+    Ok(Answer::sum(points)?.to_string())
 }
 
-pub fn run_part_2(input: &str) -> String {
-    let mut scratch_card_points_and_copies: Vec<(usize, u64)> = input
-        .lines()
-        .map(|line| line.parse::<ScratchCard>().expect("Parse error"))
-        .map(|scratch_card| (scratch_card.matching_count(), 1))
-        .collect();
+/// Cards are referred to by their position, with `params.validate` their ids have to match it
+/// by counting up from 1.
+pub fn parse_scratch_cards(
+    input: &str,
+    parse_mode: ParseMode,
+    params: &Params,
+) -> Result<Vec<ScratchCard>, parse::Error<ParseError>> {
+    let mut expected_id = 1;
 
-    for i in 0..scratch_card_points_and_copies.len() {
-        let (points, copies) = scratch_card_points_and_copies[i];
+    parse::parse_lines(input, parse_mode, |line| {
+        let scratch_card = ScratchCard::parse(line, parse_mode, params)?;
 
-        for j in 1..(points + 1) {
-            scratch_card_points_and_copies[i + j].1 += copies;
+        if params.validate && scratch_card.id != expected_id {
+            return Err(line.error(ParseError::NonSequentialCardId {
+                expected: expected_id,
+                actual: scratch_card.id,
+            }));
         }
+
+        expected_id += 1;
+
+        Ok(scratch_card)
+    })
+}
+
+pub fn run_part_2(
+    input: &str,
+    parse_mode: ParseMode,
+    params: &Params,
+) -> Result<String, challenge::Error> {
+    let scratch_cards = parse_scratch_cards(input, parse_mode, params)?;
+
+    Ok(
+        Cascade::simulate(&scratch_cards, cascade::puzzle_rule, params.out_of_range)?
+            .total_copies()
+            .to_string(),
+    )
+}
+
+/// Exports how the copies of every card were won in part 2.
+pub fn cascade(
+    input: &str,
+    parse_mode: ParseMode,
+    params: &Params,
+    format: CascadeFormat,
+) -> Result<String, challenge::Error> {
+    let scratch_cards = parse_scratch_cards(input, parse_mode, params)?;
+
+    Ok(
+        Cascade::simulate(&scratch_cards, cascade::puzzle_rule, params.out_of_range)?
+            .export(format),
+    )
+}
+
+/// `size` cards with as many numbers as in the puzzle, most of them without matches.
+/// No card wins copies of cards past the end of the table,
+/// but part 2 still grows exponentially with `size`.
+pub fn generate(rng: &mut impl Rng, size: usize) -> String {
+    const OWN_COUNT: usize = 10;
+    const WINNING_COUNT: usize = 25;
+
+    let id_width = size.to_string().len();
+
+    (1..=size)
+        .map(|id| {
+            let matching_count = if rng.random_bool(0.75) {
+                0
+            } else {
+                rng.random_range(1..=OWN_COUNT).min(size - id)
+            };
+
+            let numbers =
+                rand::seq::index::sample(rng, 99, OWN_COUNT + WINNING_COUNT - matching_count)
+                    .into_iter()
+                    .map(|idx| idx + 1)
+                    .collect_vec();
+
+            let own_numbers = &numbers[..OWN_COUNT];
+            let mut winning_numbers = own_numbers[..matching_count]
+                .iter()
+                .chain(&numbers[OWN_COUNT..])
+                .copied()
+                .collect_vec();
+            winning_numbers.shuffle(rng);
+
+            format!(
+                "Card {id:>id_width$}: {} | {}",
+                own_numbers
+                    .iter()
+                    .map(|number| format!("{number:>2}"))
+                    .join(" "),
+                winning_numbers
+                    .iter()
+                    .map(|number| format!("{number:>2}"))
+                    .join(" ")
+            )
+        })
+        .join("\n")
+}
+
+#[cfg(test)]
+mod test {
+    use super::{parse_scratch_cards, OutOfRange, Params, ParseError};
+    use crate::challenge::parse::ParseMode;
+    use itertools::Itertools;
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+
+    /// Own numbers and winning numbers of every card.
+    type Cards = Vec<(Vec<u64>, Vec<u64>)>;
+
+    /// Own numbers can be listed twice, but only count once.
+    fn reference_matching_count(own_numbers: &[u64], winning_numbers: &[u64]) -> usize {
+        own_numbers
+            .iter()
+            .enumerate()
+            .filter(|&(idx, number)| {
+                !own_numbers[..idx].contains(number) && winning_numbers.contains(number)
+            })
+            .count()
+    }
+
+    fn reference_part_1(cards: &Cards) -> u64 {
+        cards
+            .iter()
+            .map(|(own_numbers, winning_numbers)| {
+                match reference_matching_count(own_numbers, winning_numbers) {
+                    0 => 0,
+                    matching_count => 2u64.pow(matching_count as u32 - 1),
+                }
+            })
+            .sum()
+    }
+
+    /// Scratches every copy one after the other, clamping at the end of the table.
+    fn reference_part_2(cards: &Cards) -> u64 {
+        let mut copies = vec![1; cards.len()];
+
+        for (card_idx, (own_numbers, winning_numbers)) in cards.iter().enumerate() {
+            let matching_count = reference_matching_count(own_numbers, winning_numbers);
+
+            for _ in 0..copies[card_idx] {
+                for won_copies in copies.iter_mut().skip(card_idx + 1).take(matching_count) {
+                    *won_copies += 1;
+                }
+            }
+        }
+
+        copies.iter().sum()
     }
 
-    scratch_card_points_and_copies
-        .into_iter()
-        .map(|(_points, copies)| copies)
-        .sum::<u64>()
-        .to_string()
+    fn cards() -> impl Strategy<Value = Cards> {
+        vec((vec(1..20u64, 1..8), vec(1..20u64, 1..8)), 1..15)
+    }
+
+    fn input(cards: &Cards) -> String {
+        cards
+            .iter()
+            .enumerate()
+            .map(|(card_idx, (own_numbers, winning_numbers))| {
+                format!(
+                    "Card {}: {} | {}",
+                    card_idx + 1,
+                    own_numbers.iter().join(" "),
+                    winning_numbers.iter().join(" ")
+                )
+            })
+            .join("\n")
+    }
+
+    proptest! {
+        #[test]
+        fn part_1_matches_reference(cards in cards()) {
+            let answer = super::run_part_1(&input(&cards), ParseMode::Strict, &Params::default());
+
+            prop_assert_eq!(answer.unwrap(), reference_part_1(&cards).to_string());
+        }
+
+        #[test]
+        fn part_2_matches_reference(cards in cards()) {
+            let params = Params {
+                out_of_range: OutOfRange::Clamp,
+                ..Params::default()
+            };
+            let answer = super::run_part_2(&input(&cards), ParseMode::Strict, &params);
+
+            prop_assert_eq!(answer.unwrap(), reference_part_2(&cards).to_string());
+        }
+    }
+
+    #[test]
+    fn card_ids() {
+        let scratch_cards = parse_scratch_cards(
+            "Card   1: 1 | 1\nCard   2: 2 | 3",
+            ParseMode::Strict,
+            &Params::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            scratch_cards
+                .iter()
+                .map(|card| card.id())
+                .collect::<Vec<_>>(),
+            [1, 2]
+        );
+
+        let input = "Card 1: 1 | 1\nCard 3: 2 | 3";
+
+        let scratch_cards = parse_scratch_cards(input, ParseMode::Lenient, &Params::default());
+        assert_eq!(scratch_cards.unwrap()[1].id(), 3);
+
+        let params = Params {
+            validate: true,
+            ..Params::default()
+        };
+        let err = parse_scratch_cards(input, ParseMode::Lenient, &params).unwrap_err();
+        assert_eq!(
+            err.kind,
+            ParseError::NonSequentialCardId {
+                expected: 2,
+                actual: 3
+            }
+        );
+        assert_eq!(err.line, Some(1));
+    }
+
+    #[test]
+    fn duplicate_numbers() {
+        let input = "Card 1: 1 2 2 3 | 2 2 3 4";
+
+        let scratch_cards =
+            parse_scratch_cards(input, ParseMode::Strict, &Params::default()).unwrap();
+        assert_eq!(scratch_cards[0].matching_count(), 2);
+
+        let params = Params {
+            validate: true,
+            ..Params::default()
+        };
+        let err = parse_scratch_cards(input, ParseMode::Strict, &params).unwrap_err();
+        assert_eq!(err.kind, ParseError::DuplicateOwnNumber(2));
+        assert_eq!(err.span, 12..13);
+    }
 }
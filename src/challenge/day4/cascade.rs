@@ -0,0 +1,348 @@
+use super::ScratchCard;
+use crate::challenge;
+use crate::challenge::num::{Answer, Overflow};
+use itertools::Itertools;
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt::{Display, Formatter};
+use std::ops::Range;
+use strum::{Display, EnumString, EnumVariantNames};
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default, Display, EnumString, EnumVariantNames)]
+#[strum(serialize_all = "kebab-case")]
+pub enum CascadeFormat {
+    /// One row per card
+    #[default]
+    Table,
+    /// Graphviz graph with an edge for every card that won copies of another card
+    Dot,
+}
+
+/// What happens when a card wins copies of cards past the end of the table.
+/// The puzzle promises that this never happens.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default, Display, EnumString, EnumVariantNames)]
+#[strum(serialize_all = "kebab-case")]
+pub enum OutOfRange {
+    /// No copies are won of cards past the end
+    Clamp,
+    #[default]
+    Error,
+    /// Counting continues at the first card
+    Wrap,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum CascadeError {
+    OutOfRange {
+        card_id: u64,
+        won_card_id: u64,
+    },
+    /// The card is won, directly or indirectly, by a card that it wins itself
+    InfiniteCopies {
+        card_id: u64,
+    },
+    Overflow(Overflow),
+}
+
+impl Display for CascadeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CascadeError::OutOfRange {
+                card_id,
+                won_card_id,
+            } => write!(
+                f,
+                "card {card_id} wins a copy of card {won_card_id}, which is past the end of the table"
+            ),
+            CascadeError::InfiniteCopies { card_id } => write!(
+                f,
+                "card {card_id} has infinitely many copies because its copies keep winning more of it"
+            ),
+            CascadeError::Overflow(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl std::error::Error for CascadeError {}
+
+impl From<Overflow> for CascadeError {
+    fn from(err: Overflow) -> Self {
+        CascadeError::Overflow(err)
+    }
+}
+
+impl From<CascadeError> for challenge::Error {
+    fn from(err: CascadeError) -> Self {
+        match err {
+            CascadeError::Overflow(err) => challenge::Error::Overflow(err),
+            err => challenge::Error::Unsolvable(err.to_string()),
+        }
+    }
+}
+
+/// The puzzle's rule: every matching number wins one copy of one of the following cards.
+pub fn puzzle_rule(card_idx: usize, scratch_card: &ScratchCard) -> Range<usize> {
+    (card_idx + 1)..(card_idx + 1 + scratch_card.matching_count())
+}
+
+/// Outcome of scratching all cards, remembering where every copy came from.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Cascade {
+    /// For every card, how many of its copies were won by each other card
+    copies_from: Vec<BTreeMap<usize, Answer>>,
+    /// For every card, the total amount of instances including the original
+    copies: Vec<Answer>,
+    total_copies: Answer,
+}
+
+impl Cascade {
+    /// `won_cards` decides which cards an instance of a card wins a copy of.
+    /// Cards past the end of the table are handled according to `out_of_range`.
+    pub fn simulate<I: IntoIterator<Item = usize>>(
+        scratch_cards: &[ScratchCard],
+        won_cards: impl Fn(usize, &ScratchCard) -> I,
+        out_of_range: OutOfRange,
+    ) -> Result<Self, CascadeError> {
+        let card_count = scratch_cards.len();
+
+        let mut won_card_idxs = Vec::with_capacity(card_count);
+
+        for (card_idx, scratch_card) in scratch_cards.iter().enumerate() {
+            let mut won = Vec::new();
+
+            for won_card_idx in won_cards(card_idx, scratch_card) {
+                if won_card_idx < card_count {
+                    won.push(won_card_idx);
+                    continue;
+                }
+
+                match out_of_range {
+                    OutOfRange::Clamp => {}
+                    OutOfRange::Error => {
+                        return Err(CascadeError::OutOfRange {
+                            card_id: scratch_card.id(),
+                            won_card_id: won_card_idx as u64 + 1,
+                        })
+                    }
+                    OutOfRange::Wrap => won.push(won_card_idx % card_count),
+                }
+            }
+
+            won_card_idxs.push(won);
+        }
+
+        // A card can only be scratched once all of its copies have been won.
+        // With the puzzle's rule, that is just the order of the table.
+        let mut missing_wins = vec![0usize; card_count];
+
+        for &won_card_idx in won_card_idxs.iter().flatten() {
+            missing_wins[won_card_idx] += 1;
+        }
+
+        let mut scratchable: VecDeque<_> = (0..card_count)
+            .filter(|&card_idx| missing_wins[card_idx] == 0)
+            .collect();
+        let mut copies_from = vec![BTreeMap::<usize, Answer>::new(); card_count];
+        let mut all_copies = vec![None; card_count];
+
+        while let Some(card_idx) = scratchable.pop_front() {
+            let copies = Answer::one()
+                .checked_add(&Answer::sum(copies_from[card_idx].values().cloned())?)?;
+
+            for &won_card_idx in &won_card_idxs[card_idx] {
+                let won_copies = copies_from[won_card_idx].entry(card_idx).or_default();
+                *won_copies = won_copies.checked_add(&copies)?;
+
+                missing_wins[won_card_idx] -= 1;
+
+                if missing_wins[won_card_idx] == 0 {
+                    scratchable.push_back(won_card_idx);
+                }
+            }
+
+            all_copies[card_idx] = Some(copies);
+        }
+
+        // Cards that were never scratched are in or behind a cycle of cards winning each other
+        let copies: Vec<_> = all_copies
+            .into_iter()
+            .zip(scratch_cards)
+            .map(|(copies, scratch_card)| {
+                copies.ok_or(CascadeError::InfiniteCopies {
+                    card_id: scratch_card.id(),
+                })
+            })
+            .try_collect()?;
+
+        Ok(Cascade {
+            total_copies: Answer::sum(copies.iter().cloned())?,
+            copies_from,
+            copies,
+        })
+    }
+
+    /// Total amount of instances of the card, including the original.
+    pub fn copies(&self, card_idx: usize) -> &Answer {
+        &self.copies[card_idx]
+    }
+
+    /// How many copies of the card were won by each other card.
+    pub fn copies_from(&self, card_idx: usize) -> &BTreeMap<usize, Answer> {
+        &self.copies_from[card_idx]
+    }
+
+    pub fn total_copies(&self) -> &Answer {
+        &self.total_copies
+    }
+
+    pub fn export(&self, format: CascadeFormat) -> String {
+        match format {
+            CascadeFormat::Table => self.table(),
+            CascadeFormat::Dot => self.dot(),
+        }
+    }
+
+    fn table(&self) -> String {
+        let mut table = String::from("Card\tCopies\tWon from\n");
+
+        for card_idx in 0..self.copies_from.len() {
+            let won_from = self
+                .copies_from(card_idx)
+                .iter()
+                .map(|(from_idx, copies)| format!("{copies} from card {}", from_idx + 1))
+                .join(", ");
+
+            table += &format!("{}\t{}\t{won_from}\n", card_idx + 1, self.copies(card_idx));
+        }
+
+        table += &format!("Total\t{}\n", self.total_copies());
+
+        table
+    }
+
+    fn dot(&self) -> String {
+        let mut dot = String::from("digraph cascade {\n    rankdir=LR;\n");
+
+        for card_idx in 0..self.copies_from.len() {
+            dot += &format!(
+                "    card{} [label=\"Card {}\\ncopies: {}\"];\n",
+                card_idx + 1,
+                card_idx + 1,
+                self.copies(card_idx)
+            );
+        }
+
+        for card_idx in 0..self.copies_from.len() {
+            for (from_idx, copies) in self.copies_from(card_idx) {
+                dot += &format!(
+                    "    card{} -> card{} [label=\"{copies}\"];\n",
+                    from_idx + 1,
+                    card_idx + 1
+                );
+            }
+        }
+
+        dot += "}\n";
+
+        dot
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{puzzle_rule, Cascade, CascadeError, OutOfRange, ScratchCard};
+    use crate::challenge::num::Answer;
+    use std::collections::BTreeMap;
+
+    fn example() -> Vec<ScratchCard> {
+        [
+            "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53",
+            "Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19",
+            "Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1",
+            "Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83",
+            "Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36",
+            "Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11",
+        ]
+        .iter()
+        .map(|line| line.parse().unwrap())
+        .collect()
+    }
+
+    #[test]
+    fn provenance() {
+        let cascade = Cascade::simulate(&example(), puzzle_rule, OutOfRange::Error).unwrap();
+
+        assert_eq!(cascade.total_copies(), &Answer::from(30));
+        assert_eq!(cascade.copies(3), &Answer::from(8));
+        assert_eq!(
+            cascade.copies_from(3),
+            &BTreeMap::from(
+                [(0, 1), (1, 2), (2, 4)].map(|(from_idx, copies)| (from_idx, Answer::from(copies)))
+            )
+        );
+    }
+
+    #[test]
+    fn alternative_rule() {
+        // Every card with matches wins a copy of only the card directly after it
+        let cascade = Cascade::simulate(
+            &example(),
+            |card_idx, scratch_card: &ScratchCard| {
+                (scratch_card.matching_count() > 0).then_some(card_idx + 1)
+            },
+            OutOfRange::Error,
+        )
+        .unwrap();
+
+        assert_eq!(
+            (0..6)
+                .map(|card_idx| cascade.copies(card_idx).to_string())
+                .collect::<Vec<_>>(),
+            ["1", "2", "3", "4", "5", "1"]
+        );
+    }
+
+    #[test]
+    fn overflow() {
+        // Every card wins a copy of every later card, doubling the copies each time
+        let scratch_cards = vec![example()[0].clone(); 70];
+        let result = Cascade::simulate(
+            &scratch_cards,
+            |card_idx, _scratch_card: &ScratchCard| (card_idx + 1)..70,
+            OutOfRange::Error,
+        );
+
+        #[cfg(not(feature = "bigint"))]
+        assert_eq!(
+            result,
+            Err(CascadeError::Overflow(crate::challenge::num::Overflow))
+        );
+        #[cfg(feature = "bigint")]
+        assert_eq!(
+            result.unwrap().total_copies().checked_add(&Answer::one()),
+            Answer::pow2(70)
+        );
+    }
+
+    #[test]
+    fn out_of_range() {
+        // All three cards win more cards than there are left
+        let scratch_cards = example()[..3].to_vec();
+
+        assert_eq!(
+            Cascade::simulate(&scratch_cards, puzzle_rule, OutOfRange::Error),
+            Err(CascadeError::OutOfRange {
+                card_id: 1,
+                won_card_id: 4
+            })
+        );
+
+        let clamped = Cascade::simulate(&scratch_cards, puzzle_rule, OutOfRange::Clamp).unwrap();
+        assert_eq!(clamped.total_copies(), &Answer::from(7));
+
+        // Card 1 wraps around to win a copy of itself
+        assert_eq!(
+            Cascade::simulate(&scratch_cards, puzzle_rule, OutOfRange::Wrap),
+            Err(CascadeError::InfiniteCopies { card_id: 1 })
+        );
+    }
+}
@@ -0,0 +1,449 @@
+use crate::challenge::parse;
+use crate::challenge::parse::ParseMode;
+use itertools::{Either, Itertools};
+use std::fmt::{Display, Formatter};
+use std::ops::{Index, IndexMut};
+use strum::{Display, EnumString, EnumVariantNames};
+
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct Pos {
+    pub line: usize,
+    pub col: usize,
+}
+
+impl Pos {
+    pub fn new(line: usize, col: usize) -> Self {
+        Pos { line, col }
+    }
+}
+
+const NEIGHBOUR_4_OFFSETS: [(isize, isize); 4] = [(-1, 0), (0, -1), (0, 1), (1, 0)];
+const NEIGHBOUR_8_OFFSETS: [(isize, isize); 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, -1),
+    (0, 1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+];
+
+/// Which positions count as neighbours of a position.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct Neighbourhood {
+    pub shape: NeighbourhoodShape,
+    pub radius: usize,
+    /// Whether the grid's edges connect to the opposite edges, like on a torus
+    pub wrap_around: bool,
+}
+
+impl Default for Neighbourhood {
+    /// The eight directly surrounding positions.
+    fn default() -> Self {
+        Neighbourhood {
+            shape: NeighbourhoodShape::Moore,
+            radius: 1,
+            wrap_around: false,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Display, EnumString, EnumVariantNames)]
+#[strum(serialize_all = "kebab-case")]
+pub enum NeighbourhoodShape {
+    /// Positions within the radius in manhattan distance
+    VonNeumann,
+    /// Positions within the radius in chebyshev distance, i.e. including diagonals
+    Moore,
+}
+
+impl Neighbourhood {
+    /// Larger radii are rejected when parsing parameters,
+    /// every position has `(2 * radius + 1)²` offsets to check.
+    pub const MAX_RADIUS: usize = 1000;
+
+    /// Offsets within `radius`, which can be smaller than the neighbourhood's own.
+    fn offsets(self, radius: usize) -> impl Iterator<Item = (isize, isize)> {
+        let radius = isize::try_from(radius).unwrap_or(isize::MAX);
+
+        (-radius..=radius)
+            .cartesian_product(-radius..=radius)
+            .filter(move |&(line_offset, col_offset)| {
+                let in_shape = match self.shape {
+                    NeighbourhoodShape::VonNeumann => {
+                        line_offset
+                            .unsigned_abs()
+                            .saturating_add(col_offset.unsigned_abs())
+                            <= radius.unsigned_abs()
+                    }
+                    NeighbourhoodShape::Moore => true,
+                };
+
+                in_shape && (line_offset, col_offset) != (0, 0)
+            })
+    }
+
+    /// The radius beyond which no further positions of a `width` by `height` grid are reached.
+    fn max_useful_radius(self, width: usize, height: usize) -> usize {
+        match self.shape {
+            NeighbourhoodShape::VonNeumann => width.saturating_add(height),
+            NeighbourhoodShape::Moore => width.max(height),
+        }
+    }
+}
+
+/// Rectangular grid of cells, stored row by row.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Parses one cell per character. See [`parse::grid`].
+    pub fn parse<K: Copy>(
+        input: &str,
+        mode: ParseMode,
+        ragged: K,
+        parse_cell: impl FnMut(char) -> Result<T, K>,
+    ) -> Result<Self, parse::Error<K>> {
+        parse::grid(input, mode, ragged, parse_cell)
+    }
+
+    /// Takes the cells row by row. Returns `None` if there are not `width * height` of them.
+    pub fn from_cells(width: usize, height: usize, cells: Vec<T>) -> Option<Self> {
+        (width.checked_mul(height) == Some(cells.len())).then_some(Grid {
+            width,
+            height,
+            cells,
+        })
+    }
+
+    /// Returns `None` if not all rows have the same length.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Option<Self> {
+        let height = rows.len();
+        let width = rows.first().map_or(0, Vec::len);
+
+        if rows.iter().any(|row| row.len() != width) {
+            return None;
+        }
+
+        Some(Grid {
+            width,
+            height,
+            cells: rows.into_iter().flatten().collect(),
+        })
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, pos: Pos) -> bool {
+        pos.line < self.height && pos.col < self.width
+    }
+
+    pub fn get(&self, pos: Pos) -> Option<&T> {
+        self.contains(pos)
+            .then(|| &self.cells[pos.line * self.width + pos.col])
+    }
+
+    pub fn get_mut(&mut self, pos: Pos) -> Option<&mut T> {
+        self.contains(pos)
+            .then(|| &mut self.cells[pos.line * self.width + pos.col])
+    }
+
+    /// All positions, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = Pos> {
+        let width = self.width;
+
+        (0..self.height).flat_map(move |line| (0..width).map(move |col| Pos::new(line, col)))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Pos, &T)> {
+        self.positions().zip(&self.cells)
+    }
+
+    /// The positions directly above, left, right and below `pos` that are in the grid.
+    pub fn neighbours_4(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        self.offset_positions(pos, &NEIGHBOUR_4_OFFSETS)
+    }
+
+    /// Like [`Grid::neighbours_4`], but also including diagonal neighbours.
+    pub fn neighbours_8(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        self.offset_positions(pos, &NEIGHBOUR_8_OFFSETS)
+    }
+
+    /// The neighbours of `pos` in `neighbourhood` that are in the grid, each only once.
+    pub fn neighbourhood(
+        &self,
+        pos: Pos,
+        neighbourhood: Neighbourhood,
+    ) -> impl Iterator<Item = Pos> + '_ {
+        // A radius bigger than the grid only adds offsets that leave it, or wrap onto known positions
+        let radius = neighbourhood
+            .radius
+            .min(neighbourhood.max_useful_radius(self.width, self.height));
+        let offsets = neighbourhood.offsets(radius);
+
+        if neighbourhood.wrap_around {
+            // With a big radius, offsets can wrap around to the same position, or even to pos
+            Either::Left(
+                offsets
+                    .filter_map(move |offset| self.wrapping_offset(pos, offset))
+                    .filter(move |&neighbour| neighbour != pos)
+                    .unique(),
+            )
+        } else {
+            Either::Right(offsets.filter_map(move |offset| self.offset(pos, offset)))
+        }
+    }
+
+    fn offset_positions<'a>(
+        &'a self,
+        pos: Pos,
+        offsets: &'a [(isize, isize)],
+    ) -> impl Iterator<Item = Pos> + 'a {
+        offsets
+            .iter()
+            .filter_map(move |&offset| self.offset(pos, offset))
+    }
+
+    /// The position `offset` away from `pos`, if it is in the grid.
+    pub fn offset(&self, pos: Pos, (line_offset, col_offset): (isize, isize)) -> Option<Pos> {
+        let offset_pos = Pos::new(
+            pos.line.checked_add_signed(line_offset)?,
+            pos.col.checked_add_signed(col_offset)?,
+        );
+
+        self.contains(offset_pos).then_some(offset_pos)
+    }
+
+    /// Like [`Grid::offset`], but leaving the grid on one edge means entering it on the opposite edge.
+    /// Only `None` if the grid is empty or the offset does not fit into an `isize` together with `pos`.
+    pub fn wrapping_offset(
+        &self,
+        pos: Pos,
+        (line_offset, col_offset): (isize, isize),
+    ) -> Option<Pos> {
+        if self.width == 0 || self.height == 0 {
+            return None;
+        }
+
+        let wrap = |value: usize, offset: isize, len: usize| {
+            let value = isize::try_from(value).ok()?.checked_add(offset)?;
+            let len = isize::try_from(len).ok()?;
+
+            usize::try_from(value.rem_euclid(len)).ok()
+        };
+
+        Some(Pos::new(
+            wrap(pos.line, line_offset, self.height)?,
+            wrap(pos.col, col_offset, self.width)?,
+        ))
+    }
+
+    /// Positions starting at `start` and going in `direction` until leaving the grid.
+    pub fn ray(&self, start: Pos, direction: (isize, isize)) -> impl Iterator<Item = Pos> + '_ {
+        std::iter::successors(self.contains(start).then_some(start), move |&pos| {
+            self.offset(pos, direction)
+        })
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // chunks panics for a chunk size of zero
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = Pos> + '_> {
+        (0..self.width).map(|col| self.ray(Pos::new(0, col), (1, 0)))
+    }
+
+    /// Diagonals going down and right, starting with the one in the bottom left corner.
+    pub fn diagonals(&self) -> impl Iterator<Item = impl Iterator<Item = Pos> + '_> {
+        let starts = (0..self.diagonal_height())
+            .rev()
+            .map(|line| Pos::new(line, 0))
+            .chain((1..self.width).map(|col| Pos::new(0, col)));
+
+        starts.map(|start| self.ray(start, (1, 1)))
+    }
+
+    /// Diagonals going down and left, starting with the one in the top left corner.
+    pub fn anti_diagonals(&self) -> impl Iterator<Item = impl Iterator<Item = Pos> + '_> {
+        let starts = (0..self.width)
+            .map(|col| Pos::new(0, col))
+            .chain((1..self.diagonal_height()).map(|line| Pos::new(line, self.width - 1)));
+
+        starts.map(|start| self.ray(start, (1, -1)))
+    }
+
+    /// Rows that start a diagonal, a grid without columns has no diagonals even if it has rows.
+    fn diagonal_height(&self) -> usize {
+        if self.width == 0 {
+            0
+        } else {
+            self.height
+        }
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &Self::Output {
+        self.get(pos).expect("Position out of bounds")
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut Self::Output {
+        self.get_mut(pos).expect("Position out of bounds")
+    }
+}
+
+/// Renders one line per row, without separators between cells.
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for row in self.rows() {
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Grid, Neighbourhood, NeighbourhoodShape, Pos};
+    use crate::challenge::parse::ParseMode;
+    use itertools::Itertools;
+
+    fn example() -> Grid<char> {
+        Grid::parse("abc\ndef\n", ParseMode::Strict, (), Ok).unwrap()
+    }
+
+    fn cells(grid: &Grid<char>, positions: impl Iterator<Item = Pos>) -> String {
+        positions.map(|pos| grid[pos]).collect()
+    }
+
+    #[test]
+    fn indexing() {
+        let grid = example();
+
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[Pos::new(1, 2)], 'f');
+        assert_eq!(grid.get(Pos::new(2, 0)), None);
+        assert_eq!(grid.to_string(), "abc\ndef\n");
+    }
+
+    #[test]
+    fn neighbours() {
+        let grid = example();
+
+        assert_eq!(cells(&grid, grid.neighbours_4(Pos::new(0, 0))), "bd");
+        assert_eq!(cells(&grid, grid.neighbours_8(Pos::new(0, 1))), "acdef");
+
+        let von_neumann = Neighbourhood {
+            shape: NeighbourhoodShape::VonNeumann,
+            radius: 2,
+            wrap_around: false,
+        };
+        assert_eq!(
+            cells(&grid, grid.neighbourhood(Pos::new(0, 0), von_neumann)),
+            "bcde"
+        );
+
+        let wrapping_moore = Neighbourhood {
+            shape: NeighbourhoodShape::Moore,
+            radius: 1,
+            wrap_around: true,
+        };
+        assert_eq!(
+            cells(&grid, grid.neighbourhood(Pos::new(0, 0), wrapping_moore)),
+            "fdecb"
+        );
+    }
+
+    #[test]
+    fn huge_radius() {
+        let grid = example();
+
+        for shape in [NeighbourhoodShape::VonNeumann, NeighbourhoodShape::Moore] {
+            for wrap_around in [false, true] {
+                let neighbourhood = Neighbourhood {
+                    shape,
+                    radius: usize::MAX,
+                    wrap_around,
+                };
+
+                assert_eq!(
+                    cells(&grid, grid.neighbourhood(Pos::new(0, 0), neighbourhood))
+                        .chars()
+                        .sorted()
+                        .collect::<String>(),
+                    "bcdef"
+                );
+            }
+        }
+
+        assert_eq!(grid.wrapping_offset(Pos::new(1, 2), (isize::MAX, 0)), None);
+        assert_eq!(
+            grid.wrapping_offset(Pos::new(1, 2), (isize::MIN, isize::MIN)),
+            Some(Pos::new(1, 0))
+        );
+    }
+
+    #[test]
+    fn lines() {
+        let grid = example();
+
+        assert_eq!(
+            grid.columns().map(|col| cells(&grid, col)).collect_vec(),
+            ["ad", "be", "cf"]
+        );
+        assert_eq!(
+            grid.diagonals()
+                .map(|diag| cells(&grid, diag))
+                .collect_vec(),
+            ["d", "ae", "bf", "c"]
+        );
+        assert_eq!(
+            grid.anti_diagonals()
+                .map(|diag| cells(&grid, diag))
+                .collect_vec(),
+            ["a", "bd", "ce", "f"]
+        );
+    }
+
+    #[test]
+    fn zero_width_lines() {
+        let grid = Grid::<char>::from_rows(vec![vec![], vec![]]).unwrap();
+        assert_eq!((grid.width(), grid.height()), (0, 2));
+
+        assert_eq!(grid.columns().count(), 0);
+        assert_eq!(grid.diagonals().count(), 0);
+        assert_eq!(grid.anti_diagonals().count(), 0);
+
+        let parsed = Grid::parse("\n\n", ParseMode::Strict, (), Ok).unwrap();
+        assert_eq!(parsed.anti_diagonals().count(), 0);
+    }
+}
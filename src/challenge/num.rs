@@ -0,0 +1,161 @@
+use std::fmt::{Display, Formatter};
+
+#[cfg(not(feature = "bigint"))]
+type Inner = u64;
+#[cfg(feature = "bigint")]
+type Inner = num_bigint::BigUint;
+
+/// A number that answers can be accumulated in.
+///
+/// Arithmetic is checked and fails with [`Overflow`] if the result does not fit into a `u64`,
+/// unless the `bigint` feature is enabled, in which case it is exact.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub struct Answer(Inner);
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct Overflow;
+
+impl Display for Overflow {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "result does not fit into 64 bits, enable the bigint feature for exact results"
+        )
+    }
+}
+
+impl std::error::Error for Overflow {}
+
+impl Answer {
+    pub fn zero() -> Self {
+        Answer::from(0)
+    }
+
+    pub fn one() -> Self {
+        Answer::from(1)
+    }
+
+    #[cfg(not(feature = "bigint"))]
+    pub fn checked_add(&self, other: &Answer) -> Result<Answer, Overflow> {
+        self.0.checked_add(other.0).map(Answer).ok_or(Overflow)
+    }
+
+    #[cfg(feature = "bigint")]
+    pub fn checked_add(&self, other: &Answer) -> Result<Answer, Overflow> {
+        Ok(Answer(&self.0 + &other.0))
+    }
+
+    #[cfg(not(feature = "bigint"))]
+    pub fn checked_mul(&self, other: &Answer) -> Result<Answer, Overflow> {
+        self.0.checked_mul(other.0).map(Answer).ok_or(Overflow)
+    }
+
+    #[cfg(feature = "bigint")]
+    pub fn checked_mul(&self, other: &Answer) -> Result<Answer, Overflow> {
+        Ok(Answer(&self.0 * &other.0))
+    }
+
+    /// 2 to the power of `exp`.
+    #[cfg(not(feature = "bigint"))]
+    pub fn pow2(exp: usize) -> Result<Answer, Overflow> {
+        u32::try_from(exp)
+            .ok()
+            .and_then(|exp| 1u64.checked_shl(exp))
+            .map(Answer)
+            .ok_or(Overflow)
+    }
+
+    /// 2 to the power of `exp`.
+    #[cfg(feature = "bigint")]
+    pub fn pow2(exp: usize) -> Result<Answer, Overflow> {
+        Ok(Answer(Inner::from(1u8) << exp))
+    }
+
+    pub fn sum(values: impl IntoIterator<Item = Answer>) -> Result<Answer, Overflow> {
+        values
+            .into_iter()
+            .try_fold(Answer::zero(), |sum, value| sum.checked_add(&value))
+    }
+
+    pub fn product(values: impl IntoIterator<Item = Answer>) -> Result<Answer, Overflow> {
+        values
+            .into_iter()
+            .try_fold(Answer::one(), |product, value| product.checked_mul(&value))
+    }
+}
+
+impl From<u64> for Answer {
+    fn from(value: u64) -> Self {
+        Answer(Inner::from(value))
+    }
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[cfg(all(feature = "serde", not(feature = "bigint")))]
+impl serde::Serialize for Answer {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(self.0)
+    }
+}
+
+/// Serialized as a number if it fits into a `u64`, as a string otherwise.
+#[cfg(all(feature = "serde", feature = "bigint"))]
+impl serde::Serialize for Answer {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match u64::try_from(&self.0) {
+            Ok(value) => serializer.serialize_u64(value),
+            Err(_) => serializer.collect_str(self),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Answer;
+
+    #[test]
+    fn arithmetic() {
+        let values = [3, 4, 5].map(Answer::from);
+
+        assert_eq!(Answer::sum(values.clone()), Ok(Answer::from(12)));
+        assert_eq!(Answer::product(values), Ok(Answer::from(60)));
+        assert_eq!(Answer::pow2(10), Ok(Answer::from(1024)));
+        assert_eq!(Answer::pow2(63), Ok(Answer::from(1 << 63)));
+    }
+
+    #[cfg(not(feature = "bigint"))]
+    #[test]
+    fn overflow() {
+        use super::Overflow;
+
+        let max = Answer::from(u64::MAX);
+
+        assert_eq!(max.checked_add(&Answer::one()), Err(Overflow));
+        assert_eq!(max.checked_mul(&Answer::from(2)), Err(Overflow));
+        assert_eq!(Answer::pow2(64), Err(Overflow));
+        assert_eq!(Answer::sum([max.clone(), max]), Err(Overflow));
+    }
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn exact() {
+        let max = Answer::from(u64::MAX);
+
+        assert_eq!(
+            max.checked_add(&Answer::one())
+                .map(|answer| answer.to_string()),
+            Ok("18446744073709551616".to_string())
+        );
+        assert_eq!(
+            Answer::pow2(64).map(|answer| answer.to_string()),
+            Ok("18446744073709551616".to_string())
+        );
+        assert!(Answer::product([max.clone(), max])
+            .is_ok_and(|answer| answer > Answer::pow2(127).unwrap()));
+    }
+}
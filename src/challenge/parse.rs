@@ -0,0 +1,385 @@
+//! Small toolkit for parsing puzzle input.
+//!
+//! Parsers work on [`Span`]s, which remember where in their line they are,
+//! so that every [`Error`] can point at the exact part of the input that is wrong.
+
+use crate::challenge::grid::Grid;
+use itertools::{Either, Itertools};
+use std::fmt::{Display, Formatter};
+use std::ops::Range;
+use std::str::FromStr;
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+pub enum ParseMode {
+    /// Only accepts input in exactly the format of the puzzle input.
+    Strict,
+    /// Also accepts CRLF line endings, any inline whitespace around separators and blank lines.
+    #[default]
+    Lenient,
+}
+
+/// A parse error of kind `K`, e.g. a day's `ParseError`, together with where it happened.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Error<K> {
+    pub kind: K,
+    /// Zero-based line number, if the error happened while parsing a multi-line input.
+    pub line: Option<usize>,
+    /// Byte range in the line.
+    pub span: Range<usize>,
+}
+
+impl<K: Display> Display for Error<K> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if let Some(line) = self.line {
+            write!(f, "line {}, ", line + 1)?;
+        }
+
+        let first_column = self.span.start + 1;
+        let last_column = self.span.end;
+
+        if last_column > first_column {
+            write!(f, "columns {first_column}-{last_column}: {}", self.kind)
+        } else {
+            write!(f, "column {first_column}: {}", self.kind)
+        }
+    }
+}
+
+impl<K: Display + std::fmt::Debug> std::error::Error for Error<K> {}
+
+/// Part of an input line together with its position in that line.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct Span<'a> {
+    text: &'a str,
+    start: usize,
+}
+
+impl<'a> Span<'a> {
+    pub fn new(text: &'a str) -> Self {
+        Span { text, start: 0 }
+    }
+
+    pub fn text(&self) -> &'a str {
+        self.text
+    }
+
+    pub fn range(&self) -> Range<usize> {
+        self.start..(self.start + self.text.len())
+    }
+
+    pub fn error<K>(&self, kind: K) -> Error<K> {
+        Error {
+            kind,
+            line: None,
+            span: self.range(),
+        }
+    }
+
+    /// `sub` must be a slice of this span's text.
+    fn sub_span(&self, sub: &'a str) -> Span<'a> {
+        let offset = sub.as_ptr() as usize - self.text.as_ptr() as usize;
+
+        Span {
+            text: sub,
+            start: self.start + offset,
+        }
+    }
+
+    /// Without leading whitespace, which the puzzle uses to align columns.
+    pub fn trim_start(self) -> Span<'a> {
+        self.sub_span(self.text.trim_start())
+    }
+
+    /// Checks that the whole span is `literal`.
+    pub fn literal<K>(self, literal: &str, kind: K) -> Result<(), Error<K>> {
+        if self.text == literal {
+            Ok(())
+        } else {
+            Err(self.error(kind))
+        }
+    }
+
+    /// Parses the whole span as an unsigned integer. Signs are not accepted.
+    pub fn unsigned<T: FromStr, K>(self, kind: K) -> Result<T, Error<K>> {
+        if self.text.is_empty() || !self.text.bytes().all(|byte| byte.is_ascii_digit()) {
+            return Err(self.error(kind));
+        }
+
+        // Can still fail if the number is too big
+        self.text.parse().map_err(|_| self.error(kind))
+    }
+
+    /// Splits at every occurrence of `separator` as it appears in the puzzle input.
+    /// In lenient mode, whitespace around the separator is ignored,
+    /// and a separator consisting only of whitespace matches any whitespace.
+    pub fn split(self, mode: ParseMode, separator: &'static str) -> impl Iterator<Item = Span<'a>> {
+        let parts = match mode {
+            ParseMode::Strict => Either::Left(self.text.split(separator)),
+            ParseMode::Lenient => match separator.trim() {
+                "" => Either::Right(Either::Left(self.text.split_whitespace())),
+                separator => {
+                    Either::Right(Either::Right(self.text.split(separator).map(str::trim)))
+                }
+            },
+        };
+
+        parts.map(move |part| self.sub_span(part))
+    }
+
+    /// Like [`Span::split`], but only splits at the first occurrence.
+    pub fn split_once<K>(
+        self,
+        mode: ParseMode,
+        separator: &'static str,
+        kind: K,
+    ) -> Result<(Span<'a>, Span<'a>), Error<K>> {
+        let parts = match mode {
+            ParseMode::Strict => self.text.split_once(separator),
+            ParseMode::Lenient => match separator.trim() {
+                "" => self
+                    .text
+                    .trim()
+                    .split_once(char::is_whitespace)
+                    .map(|(a, b)| (a, b.trim_start())),
+                separator => self
+                    .text
+                    .split_once(separator)
+                    .map(|(a, b)| (a.trim(), b.trim())),
+            },
+        };
+
+        let (a, b) = parts.ok_or_else(|| self.error(kind))?;
+
+        Ok((self.sub_span(a), self.sub_span(b)))
+    }
+
+    /// Parses a list of items separated by `separator`.
+    pub fn separated<T, K>(
+        self,
+        mode: ParseMode,
+        separator: &'static str,
+        parse_item: impl FnMut(Span<'a>) -> Result<T, Error<K>>,
+    ) -> Result<Vec<T>, Error<K>> {
+        self.split(mode, separator).map(parse_item).try_collect()
+    }
+
+    /// Parses a record like `Game 12` with the given `key`, and returns the value.
+    pub fn keyed<K: Copy>(
+        self,
+        mode: ParseMode,
+        key: &str,
+        separator: &'static str,
+        kind: K,
+    ) -> Result<Span<'a>, Error<K>> {
+        let (actual_key, value) = self.split_once(mode, separator, kind)?;
+        actual_key.literal(key, kind)?;
+
+        Ok(value)
+    }
+
+    /// Parses a list of pairs like `3 blue, 4 red`.
+    pub fn pairs<K: Copy>(
+        self,
+        mode: ParseMode,
+        list_separator: &'static str,
+        pair_separator: &'static str,
+        kind: K,
+    ) -> Result<Vec<(Span<'a>, Span<'a>)>, Error<K>> {
+        self.separated(mode, list_separator, |pair| {
+            pair.split_once(mode, pair_separator, kind)
+        })
+    }
+}
+
+/// Splits the input into lines, together with their zero-based line number.
+/// In lenient mode, lines are trimmed and blank lines are skipped.
+pub fn lines(input: &str, mode: ParseMode) -> impl Iterator<Item = (usize, Span<'_>)> {
+    let lines = match mode {
+        ParseMode::Strict => Either::Left(input.strip_suffix('\n').unwrap_or(input).split('\n')),
+        ParseMode::Lenient => Either::Right(input.split('\n')),
+    }
+    .enumerate();
+
+    lines.filter_map(move |(line_number, line)| {
+        let line = Span::new(line);
+
+        match mode {
+            ParseMode::Strict => Some((line_number, line)),
+            ParseMode::Lenient => {
+                let trimmed = line.sub_span(line.text.trim());
+                (!trimmed.text.is_empty()).then_some((line_number, trimmed))
+            }
+        }
+    })
+}
+
+/// Parses every line with `parse_line`, adding the line number to errors.
+pub fn parse_lines<'a, T, K>(
+    input: &'a str,
+    mode: ParseMode,
+    mut parse_line: impl FnMut(Span<'a>) -> Result<T, Error<K>>,
+) -> Result<Vec<T>, Error<K>> {
+    lines(input, mode)
+        .map(|(line_number, line)| {
+            parse_line(line).map_err(|err| Error {
+                line: Some(line_number),
+                ..err
+            })
+        })
+        .try_collect()
+}
+
+/// Where a grid row was in the input, which differs from its index when blank lines are skipped.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct RowOrigin {
+    /// Zero-based line number
+    pub line: usize,
+    /// Byte offset of the row's first cell in the line
+    pub start: usize,
+}
+
+impl RowOrigin {
+    /// An error about the cells in `cols` of this row.
+    pub fn error<K>(&self, kind: K, cols: Range<usize>) -> Error<K> {
+        Error {
+            kind,
+            line: Some(self.line),
+            span: (self.start + cols.start)..(self.start + cols.end),
+        }
+    }
+}
+
+/// Parses a rectangular grid of characters, one cell per character.
+/// Rows that are longer or shorter than the first row are an error of kind `ragged`.
+pub fn grid<T, K: Copy>(
+    input: &str,
+    mode: ParseMode,
+    ragged: K,
+    parse_cell: impl FnMut(char) -> Result<T, K>,
+) -> Result<Grid<T>, Error<K>> {
+    grid_with_origins(input, mode, ragged, parse_cell).map(|(grid, _origins)| grid)
+}
+
+/// Like [`grid`], but also returns where every row was in the input,
+/// for errors found after parsing.
+pub fn grid_with_origins<T, K: Copy>(
+    input: &str,
+    mode: ParseMode,
+    ragged: K,
+    mut parse_cell: impl FnMut(char) -> Result<T, K>,
+) -> Result<(Grid<T>, Vec<RowOrigin>), Error<K>> {
+    let mut width = None;
+    let mut origins = Vec::new();
+    let mut cells = Vec::new();
+
+    for (line_number, line) in lines(input, mode) {
+        let row_start = cells.len();
+
+        for (idx, cell) in line.text.char_indices() {
+            let cell = parse_cell(cell).map_err(|kind| Error {
+                line: Some(line_number),
+                ..line
+                    .sub_span(&line.text[idx..(idx + cell.len_utf8())])
+                    .error(kind)
+            })?;
+
+            cells.push(cell);
+        }
+
+        let row_len = cells.len() - row_start;
+
+        if width.is_none() {
+            // Cells are pushed directly into the grid, so avoid growing it row by row
+            cells.reserve(row_len * (input.len() / (line.text.len() + 1)));
+        }
+
+        if *width.get_or_insert(row_len) != row_len {
+            return Err(Error {
+                line: Some(line_number),
+                ..line.error(ragged)
+            });
+        }
+
+        origins.push(RowOrigin {
+            line: line_number,
+            start: line.start,
+        });
+    }
+
+    // All rows have the same length
+    let grid = Grid::from_cells(width.unwrap_or(0), origins.len(), cells).unwrap();
+
+    Ok((grid, origins))
+}
+
+#[cfg(test)]
+mod test {
+    use super::{grid, grid_with_origins, parse_lines, Error, ParseMode, RowOrigin, Span};
+    use crate::challenge::grid::Grid;
+
+    #[test]
+    fn spans() {
+        let line = Span::new("Game 12:  3 blue");
+        let (game_def, rest) = line.split_once(ParseMode::Lenient, ": ", ()).unwrap();
+
+        assert_eq!(game_def.range(), 0..7);
+        assert_eq!(rest.range(), 10..16);
+        assert_eq!(
+            game_def
+                .keyed(ParseMode::Strict, "Game", " ", ())
+                .and_then(|id| id.unsigned::<u64, _>(())),
+            Ok(12)
+        );
+        assert_eq!(
+            rest.unsigned::<u64, _>(()),
+            Err(Error {
+                kind: (),
+                line: None,
+                span: 10..16
+            })
+        );
+    }
+
+    #[test]
+    fn line_numbers() {
+        let input = "1\r\n\r\n2\r\nx\r\n";
+
+        assert_eq!(
+            parse_lines(input, ParseMode::Lenient, |line| line
+                .unsigned::<u64, _>("nan"))
+            .unwrap_err()
+            .to_string(),
+            "line 4, column 1: nan"
+        );
+        assert!(parse_lines(input, ParseMode::Strict, |line| line.unsigned::<u64, _>(())).is_err());
+    }
+
+    #[test]
+    fn grids() {
+        let parse_cell = |cell: char| cell.to_digit(10).ok_or("digit");
+
+        assert_eq!(
+            grid("12\n34\n", ParseMode::Strict, "ragged", parse_cell),
+            Ok(Grid::from_rows(vec![vec![1, 2], vec![3, 4]]).unwrap())
+        );
+        assert_eq!(
+            grid("12\n3\n", ParseMode::Strict, "ragged", parse_cell).map_err(|err| err.line),
+            Err(Some(1))
+        );
+        assert_eq!(
+            grid("12\n3x\n", ParseMode::Strict, "ragged", parse_cell).map_err(|err| err.span),
+            Err(1..2)
+        );
+
+        let (_grid, origins) =
+            grid_with_origins("\n  12\r\n\n34\n", ParseMode::Lenient, "ragged", parse_cell)
+                .unwrap();
+        assert_eq!(
+            origins,
+            [
+                RowOrigin { line: 1, start: 2 },
+                RowOrigin { line: 3, start: 0 }
+            ]
+        );
+    }
+}
@@ -0,0 +1,525 @@
+use aoc_2023::challenge::day2::CubeCollection;
+use aoc_2023::challenge::day3::{Combination, ExplainFormat, RenderFormat};
+use aoc_2023::challenge::day4::{CascadeFormat, OutOfRange};
+use aoc_2023::challenge::grid::NeighbourhoodShape;
+use aoc_2023::config::{
+    self, parse_adjacent_numbers, parse_gear_symbols, parse_neighbourhood_radius, Config,
+};
+#[cfg(feature = "server")]
+use aoc_2023::server;
+use aoc_2023::{challenge, examples, rundata, scaffold};
+use clap::{ArgAction, Parser, Subcommand};
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Runs the current challenge if no command is given.
+///
+/// Settings are taken from, in order of precedence: these flags, the environment variables
+/// RUNDATA_DIR, TESTDATA_DIR, CURRENT_DAY and CURRENT_PART, aoc.toml, and the built-in defaults
+#[derive(Clone, Debug, Parser)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+    /// Configuration file, aoc.toml in the working directory if it exists
+    #[arg(long, global = true)]
+    config: Option<PathBuf>,
+    /// Day of the current challenge
+    #[arg(long)]
+    day: Option<u8>,
+    /// Part of the current challenge
+    #[arg(long)]
+    part: Option<u8>,
+    /// How many seconds the current challenge may run
+    #[arg(long, value_parser = clap::value_parser!(u64).range(1..))]
+    timeout: Option<u64>,
+    /// The bag that day 2 games are checked against, e.g. "12 red, 13 green, 14 blue"
+    #[arg(long, global = true, value_parser = parse_bag, conflicts_with = "bag_file")]
+    bag: Option<CubeCollection>,
+    /// File containing the bag for day 2, in the same format as --bag
+    #[arg(long, global = true)]
+    bag_file: Option<PathBuf>,
+    /// Shape of the day 3 neighbourhood: moore (including diagonals) or von-neumann
+    #[arg(long, global = true)]
+    neighbourhood: Option<NeighbourhoodShape>,
+    /// How far away cells can be to count as adjacent in day 3, at most 1000
+    #[arg(long, global = true, value_parser = parse_neighbourhood_radius)]
+    neighbourhood_radius: Option<usize>,
+    /// Connect the edges of the day 3 schematic to their opposite edges,
+    /// --wrap-around=false to turn it off
+    #[arg(
+        long,
+        global = true,
+        action = ArgAction::Set,
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "true"
+    )]
+    wrap_around: Option<bool>,
+    /// Symbols that can be gears in day 3 part 2, e.g. "*#"
+    #[arg(long, global = true, value_parser = parse_gear_symbols)]
+    // The full path stops clap from taking each character as a separate value
+    gear_symbols: Option<::std::vec::Vec<char>>,
+    /// How many numbers have to be adjacent to a day 3 gear, e.g. "2" or "2-4"
+    #[arg(long, global = true, value_parser = parse_adjacent_numbers)]
+    gear_adjacent_numbers: Option<RangeInclusive<usize>>,
+    /// How the numbers adjacent to a day 3 gear are combined: product, sum or max
+    #[arg(long, global = true)]
+    gear_combination: Option<Combination>,
+    /// What happens when a day 4 card wins copies of cards past the end of the table:
+    /// error, clamp or wrap
+    #[arg(long, global = true)]
+    out_of_range: Option<OutOfRange>,
+    /// Reject day 4 cards that list a number twice or whose ids don't count up from 1,
+    /// --validate-cards=false to accept them
+    #[arg(
+        long,
+        global = true,
+        action = ArgAction::Set,
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "true"
+    )]
+    validate_cards: Option<bool>,
+    /// Only accept input in exactly the puzzle's format
+    /// instead of tolerating CRLF line endings and extra whitespace, --strict=false to tolerate them
+    #[arg(
+        long,
+        global = true,
+        action = ArgAction::Set,
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "true"
+    )]
+    strict: Option<bool>,
+}
+
+#[derive(Clone, Debug, Subcommand)]
+enum Command {
+    /// Day 2 tools
+    Day2 {
+        #[command(subcommand)]
+        command: Day2Command,
+    },
+    /// Day 3 tools
+    Day3 {
+        #[command(subcommand)]
+        command: Day3Command,
+    },
+    /// Day 4 tools
+    Day4 {
+        #[command(subcommand)]
+        command: Day4Command,
+    },
+    /// Prints random puzzle input for a day
+    Generate {
+        day: u8,
+        /// The same seed always gives the same input
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+        /// Number of lines, for day 3 also the number of columns
+        #[arg(long, default_value_t = 100)]
+        size: usize,
+    },
+    /// Creates the solver module, challenge variants and test cases for a new day
+    NewDay {
+        day: u8,
+        /// Root directory of this crate
+        #[arg(long, default_value = ".")]
+        root: PathBuf,
+    },
+    /// Saves the examples of a locally saved puzzle page as test cases
+    ExtractExamples {
+        /// Puzzle description HTML file
+        page: PathBuf,
+        /// Read from the page's title by default
+        #[arg(long)]
+        day: Option<u8>,
+        /// Which example to use if a part has several, starting at 1. Asks by default
+        #[arg(long)]
+        example: Option<usize>,
+    },
+    /// Shows every challenge with its test results, answers and runtimes,
+    /// and runs, tests or benchmarks them
+    #[cfg(feature = "tui")]
+    Dashboard,
+    /// Starts a local HTTP server with GET /challenges and POST /solve/{day}/{part}
+    #[cfg(feature = "server")]
+    Serve {
+        #[arg(long, default_value = "127.0.0.1:8080")]
+        address: String,
+    },
+}
+
+#[derive(Clone, Debug, Subcommand)]
+enum Day2Command {
+    /// Explains the minimal bag of every game and which draws don't fit into the bag
+    Report {
+        /// Day 2 input file
+        input: PathBuf,
+    },
+    /// Converts puzzle input into JSON
+    #[cfg(feature = "serde")]
+    ToJson {
+        /// Day 2 input file
+        input: PathBuf,
+    },
+    /// Converts JSON games back into puzzle input
+    #[cfg(feature = "serde")]
+    FromJson {
+        /// JSON file containing an array of games
+        input: PathBuf,
+    },
+}
+
+#[derive(Clone, Debug, Subcommand)]
+enum Day3Command {
+    /// Draws the schematic with part numbers, other numbers and gears highlighted
+    Render {
+        /// Day 3 input file
+        input: PathBuf,
+        /// ansi for the terminal or html for a standalone page
+        #[arg(long)]
+        format: Option<RenderFormat>,
+    },
+    /// Lists every number with the symbols that make it a part number,
+    /// and every gear with its numbers and ratio
+    Explain {
+        /// Day 3 input file
+        input: PathBuf,
+        /// text, or json if built with the serde feature
+        #[arg(long)]
+        format: Option<ExplainFormat>,
+    },
+}
+
+#[derive(Clone, Debug, Subcommand)]
+enum Day4Command {
+    /// Shows how many copies of every card were won from which earlier cards
+    Cascade {
+        /// Day 4 input file
+        input: PathBuf,
+        /// table, or dot for a Graphviz graph
+        #[arg(long)]
+        format: Option<CascadeFormat>,
+    },
+}
+
+fn parse_bag(s: &str) -> Result<CubeCollection, String> {
+    s.trim()
+        .parse()
+        .map_err(|err| format!("Invalid bag: {err}"))
+}
+
+pub fn main() {
+    _ = dotenv::dotenv();
+
+    let cli = Cli::parse();
+
+    let mut config = match &cli.config {
+        Some(path) => Config::load(path),
+        None => Config::load_or_default(config::DEFAULT_PATH.as_ref()),
+    }
+    .unwrap_or_else(|err| panic!("Invalid configuration: {err}"));
+
+    config
+        .apply_env()
+        .unwrap_or_else(|err| panic!("Invalid configuration: {err}"));
+
+    if let Some(day) = cli.day {
+        config.day = Some(day);
+    }
+
+    if let Some(part) = cli.part {
+        config.part = Some(part);
+    }
+
+    if let Some(timeout) = cli.timeout {
+        config.timeout = Some(Duration::from_secs(timeout));
+    }
+
+    let params = &mut config.params;
+
+    if let Some(neighbourhood) = cli.neighbourhood {
+        params.day3.neighbourhood.shape = neighbourhood;
+    }
+
+    if let Some(neighbourhood_radius) = cli.neighbourhood_radius {
+        params.day3.neighbourhood.radius = neighbourhood_radius;
+    }
+
+    if let Some(wrap_around) = cli.wrap_around {
+        params.day3.neighbourhood.wrap_around = wrap_around;
+    }
+
+    if let Some(gear_symbols) = cli.gear_symbols {
+        params.day3.gear_rule.symbols = gear_symbols;
+    }
+
+    if let Some(gear_adjacent_numbers) = cli.gear_adjacent_numbers {
+        params.day3.gear_rule.adjacent_numbers = gear_adjacent_numbers;
+    }
+
+    if let Some(gear_combination) = cli.gear_combination {
+        params.day3.gear_rule.combination = gear_combination;
+    }
+
+    if let Some(out_of_range) = cli.out_of_range {
+        params.day4.out_of_range = out_of_range;
+    }
+
+    if let Some(validate_cards) = cli.validate_cards {
+        params.day4.validate = validate_cards;
+    }
+
+    if let Some(strict) = cli.strict {
+        params.parse_mode = config::parse_mode(strict);
+    }
+
+    if let Some(bag) = cli.bag {
+        params.day2.bag = bag;
+    } else if let Some(bag_file) = cli.bag_file {
+        let bag = fs::read_to_string(bag_file).expect("Could not read bag file");
+        params.day2.bag = parse_bag(&bag).expect("Could not parse bag file");
+    }
+
+    match cli.command {
+        None => run_current_challenge(&config),
+        Some(Command::Day2 { command }) => run_day2_command(command, &config.params),
+        Some(Command::Day3 { command }) => run_day3_command(command, &config),
+        Some(Command::Day4 { command }) => run_day4_command(command, &config),
+        Some(Command::Generate { day, seed, size }) => {
+            let input = challenge::generate(day, seed, size).expect("No generator for this day");
+
+            println!("{input}");
+        }
+        Some(Command::NewDay { day, root }) => {
+            let changes = scaffold::new_day(&root, day)
+                .unwrap_or_else(|err| panic!("Could not create day {day}: {err}"));
+
+            for path in changes.created {
+                println!("Created {}", path.display());
+            }
+
+            for path in changes.modified {
+                println!("Modified {}", path.display());
+            }
+        }
+        Some(Command::ExtractExamples { page, day, example }) => {
+            extract_examples(&page, day, example, &config.testdata_dir)
+        }
+        #[cfg(feature = "tui")]
+        Some(Command::Dashboard) => crate::tui::run(config).expect("Could not run dashboard"),
+        #[cfg(feature = "server")]
+        Some(Command::Serve { address }) => {
+            println!("Listening on http://{address}");
+
+            server::serve(&address, &config.params, config.timeout).expect("Could not run server");
+        }
+    }
+}
+
+fn extract_examples(page: &Path, day: Option<u8>, example: Option<usize>, testdata_dir: &Path) {
+    let html = fs::read_to_string(page).expect("Could not read puzzle page");
+
+    let day = day
+        .or_else(|| examples::day_number(&html))
+        .expect("Could not find the day in the page's title, provide --day");
+
+    let parts = examples::extract(&html);
+
+    for (part_idx, part) in parts.iter().enumerate().take(2) {
+        let part_number = part_idx + 1;
+
+        // Part two often reuses the example of part one
+        let blocks = match (part.blocks.is_empty(), part_idx.checked_sub(1)) {
+            (true, Some(previous_idx)) => &parts[previous_idx].blocks,
+            _ => &part.blocks,
+        };
+
+        let Some(answer) = part.answer() else {
+            println!("Part {part_number} has no example answer, skipping it");
+            continue;
+        };
+
+        let block_idx = match (blocks.len(), example) {
+            (0, _) => {
+                println!("Part {part_number} has no example, skipping it");
+                continue;
+            }
+            (1, _) => 0,
+            (block_count, Some(example)) => {
+                assert!(
+                    (1..=block_count).contains(&example),
+                    "Part {part_number} only has {block_count} examples"
+                );
+                example - 1
+            }
+            (block_count, None) => ask_for_example(part_number, blocks, answer, block_count),
+        };
+
+        let test_case = examples::write_test_case(
+            testdata_dir,
+            day,
+            part_number as u8,
+            &blocks[block_idx],
+            answer,
+        )
+        .expect("Could not write test case");
+
+        match test_case {
+            examples::TestCase::Created(path) => println!("Created {}", path.display()),
+            examples::TestCase::AlreadyExists(path) => {
+                println!("Skipped, {} is the same", path.display())
+            }
+        }
+    }
+}
+
+fn ask_for_example(
+    part_number: usize,
+    blocks: &[String],
+    answer: &str,
+    block_count: usize,
+) -> usize {
+    for (block_idx, block) in blocks.iter().enumerate() {
+        println!("--- Example {} ---\n{block}", block_idx + 1);
+    }
+
+    loop {
+        print!("Which example of part {part_number} has the answer {answer}? [1-{block_count}] ");
+        io::stdout().flush().expect("Could not write");
+
+        let mut line = String::new();
+        let bytes_read = io::stdin()
+            .lock()
+            .read_line(&mut line)
+            .expect("Could not read answer");
+        assert!(bytes_read > 0, "No example chosen");
+
+        match line.trim().parse::<usize>() {
+            Ok(example) if (1..=block_count).contains(&example) => return example - 1,
+            _ => println!("Enter a number from 1 to {block_count}"),
+        }
+    }
+}
+
+fn run_day2_command(command: Day2Command, params: &challenge::Params) {
+    match command {
+        Day2Command::Report { input } => {
+            let input = fs::read_to_string(input).expect("Could not read in file");
+            let report = challenge::day2::report(&input, params.parse_mode, &params.day2)
+                .unwrap_or_else(|err| panic!("Could not create report: {err}"));
+
+            print!("{report}");
+        }
+        #[cfg(feature = "serde")]
+        Day2Command::ToJson { input } => {
+            let input = fs::read_to_string(input).expect("Could not read in file");
+            let json = challenge::day2::to_json(&input, params.parse_mode)
+                .expect("Could not convert to JSON");
+
+            println!("{json}");
+        }
+        #[cfg(feature = "serde")]
+        Day2Command::FromJson { input } => {
+            let json = fs::read_to_string(input).expect("Could not read in file");
+            let input = challenge::day2::from_json(&json).expect("Could not convert from JSON");
+
+            println!("{input}");
+        }
+    }
+}
+
+fn run_day3_command(command: Day3Command, config: &Config) {
+    let params = &config.params;
+
+    match command {
+        Day3Command::Render { input, format } => {
+            let input = fs::read_to_string(input).expect("Could not read in file");
+            let format = format.unwrap_or(config.render_format);
+            let rendered = challenge::day3::render(&input, params.parse_mode, &params.day3, format)
+                .unwrap_or_else(|err| panic!("Parse error: {err}"));
+
+            print!("{rendered}");
+        }
+        Day3Command::Explain { input, format } => {
+            let input = fs::read_to_string(input).expect("Could not read in file");
+            let format = format.unwrap_or(config.explain_format);
+            let explanation =
+                challenge::day3::explain(&input, params.parse_mode, &params.day3, format)
+                    .unwrap_or_else(|err| panic!("Could not explain schematic: {err}"));
+
+            print!("{explanation}");
+        }
+    }
+}
+
+fn run_day4_command(command: Day4Command, config: &Config) {
+    let params = &config.params;
+
+    match command {
+        Day4Command::Cascade { input, format } => {
+            let input = fs::read_to_string(input).expect("Could not read in file");
+            let format = format.unwrap_or(config.cascade_format);
+            let cascade = challenge::day4::cascade(&input, params.parse_mode, &params.day4, format)
+                .unwrap_or_else(|err| panic!("Could not simulate cascade: {err}"));
+
+            print!("{cascade}");
+        }
+    }
+}
+
+fn run_current_challenge(config: &Config) {
+    let base_path = config
+        .rundata_dir
+        .as_ref()
+        .expect("Set directories.rundata in aoc.toml, or the RUNDATA_DIR environment variable");
+
+    if config.day.is_none() || config.part.is_none() {
+        panic!("Set current.day and current.part in aoc.toml, the CURRENT_DAY and CURRENT_PART environment variables, or --day and --part");
+    }
+
+    let challenge = config
+        .current_challenge()
+        .expect("Challenge does not exist");
+
+    let part_run_dir_path = rundata::part_dir_path(base_path, challenge);
+
+    let input = rundata::read_input(&part_run_dir_path).expect("Could not read in file");
+
+    let timed_run = move |params: &challenge::Params| {
+        let start = Instant::now();
+        let output = challenge.run(&input, params);
+
+        output.map(|output| (output, start.elapsed()))
+    };
+
+    let (output, runtime) = match config.timeout {
+        None => timed_run(&config.params),
+        Some(timeout) => {
+            let (sender, receiver) = mpsc::channel();
+            let params = config.params.clone();
+
+            thread::spawn(move || _ = sender.send(timed_run(&params)));
+
+            match receiver.recv_timeout(timeout) {
+                Ok(output) => output,
+                Err(RecvTimeoutError::Timeout) => panic!(
+                    "Challenge did not finish within {} seconds",
+                    timeout.as_secs()
+                ),
+                // The solver thread already printed its panic message
+                Err(RecvTimeoutError::Disconnected) => panic!("Challenge panicked"),
+            }
+        }
+    }
+    .unwrap_or_else(|err| panic!("Could not solve challenge: {err}"));
+
+    let timestamp = rundata::save_answer(&part_run_dir_path, &output).expect("Could not write");
+    rundata::save_runtime(&part_run_dir_path, &timestamp, runtime).expect("Could not write");
+}
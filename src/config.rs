@@ -0,0 +1,472 @@
+//! Settings from `aoc.toml`.
+//!
+//! Every key is optional. Settings are taken from, in order of precedence:
+//! command line flags, the environment variables `RUNDATA_DIR`, `TESTDATA_DIR`, `CURRENT_DAY`
+//! and `CURRENT_PART`, `aoc.toml`, and finally the built-in defaults.
+
+use crate::challenge;
+use crate::challenge::day2::CubeCollection;
+use crate::challenge::day3::{self, ExplainFormat, RenderFormat};
+use crate::challenge::day4::CascadeFormat;
+use crate::challenge::grid::Neighbourhood;
+use crate::challenge::parse::ParseMode;
+use crate::Challenge;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::Duration;
+use strum::VariantNames;
+
+mod file;
+
+/// Where the configuration is read from if no other path is given.
+pub const DEFAULT_PATH: &str = "aoc.toml";
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Config {
+    /// Contains `day<N>/part<M>/in.txt` for the current challenge, which writes its output next to it
+    pub rundata_dir: Option<PathBuf>,
+    /// Contains the test cases, see [`crate::run_tests`]
+    pub testdata_dir: PathBuf,
+    /// Day of the challenge that is run if no command is given
+    pub day: Option<u8>,
+    /// Part of the challenge that is run if no command is given
+    pub part: Option<u8>,
+    pub params: challenge::Params,
+    /// How long the current challenge may run
+    pub timeout: Option<Duration>,
+    pub render_format: RenderFormat,
+    pub explain_format: ExplainFormat,
+    pub cascade_format: CascadeFormat,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            rundata_dir: None,
+            testdata_dir: PathBuf::from("testdata"),
+            day: None,
+            part: None,
+            params: challenge::Params::default(),
+            timeout: None,
+            render_format: RenderFormat::default(),
+            explain_format: ExplainFormat::default(),
+            cascade_format: CascadeFormat::default(),
+        }
+    }
+}
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum Error {
+    Read(String),
+    /// Not valid TOML, or a key is unknown or has the wrong type
+    Syntax(String),
+    Invalid {
+        key: String,
+        message: String,
+    },
+    Env {
+        var: String,
+        message: String,
+    },
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Read(err) => write!(f, "could not read configuration: {err}"),
+            Error::Syntax(err) => write!(f, "{err}"),
+            Error::Invalid { key, message } => write!(f, "invalid value for {key}: {message}"),
+            Error::Env { var, message } => {
+                write!(f, "invalid {var} environment variable: {message}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl FromStr for Config {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let file: file::File = toml::from_str(s).map_err(|err| Error::Syntax(err.to_string()))?;
+
+        let mut config = Config::default();
+
+        if let Some(rundata) = file.directories.rundata {
+            config.rundata_dir = Some(rundata);
+        }
+
+        if let Some(testdata) = file.directories.testdata {
+            config.testdata_dir = testdata;
+        }
+
+        config.day = file.current.day;
+        config.part = file.current.part;
+
+        if let Some(strict) = file.input.strict {
+            config.params.parse_mode = parse_mode(strict);
+        }
+
+        if let Some(bag) = file.day2.bag {
+            config.params.day2.bag = bag
+                .trim()
+                .parse::<CubeCollection>()
+                .map_err(|err| invalid("day2.bag", &err.to_string()))?;
+        }
+
+        let day3 = &mut config.params.day3;
+
+        if let Some(neighbourhood) = file.day3.neighbourhood {
+            day3.neighbourhood.shape = parse_variant("day3.neighbourhood", &neighbourhood)?;
+        }
+
+        if let Some(neighbourhood_radius) = file.day3.neighbourhood_radius {
+            day3.neighbourhood.radius = check_neighbourhood_radius(neighbourhood_radius)
+                .map_err(|message| invalid("day3.neighbourhood_radius", &message))?;
+        }
+
+        if let Some(wrap_around) = file.day3.wrap_around {
+            day3.neighbourhood.wrap_around = wrap_around;
+        }
+
+        if let Some(gear_symbols) = file.day3.gear_symbols {
+            day3.gear_rule.symbols = parse_gear_symbols(&gear_symbols)
+                .map_err(|message| invalid("day3.gear_symbols", &message))?;
+        }
+
+        if let Some(gear_adjacent_numbers) = file.day3.gear_adjacent_numbers {
+            day3.gear_rule.adjacent_numbers = parse_adjacent_numbers(&gear_adjacent_numbers)
+                .map_err(|message| invalid("day3.gear_adjacent_numbers", &message))?;
+        }
+
+        if let Some(gear_combination) = file.day3.gear_combination {
+            day3.gear_rule.combination = parse_variant("day3.gear_combination", &gear_combination)?;
+        }
+
+        if let Some(out_of_range) = file.day4.out_of_range {
+            config.params.day4.out_of_range = parse_variant("day4.out_of_range", &out_of_range)?;
+        }
+
+        if let Some(validate_cards) = file.day4.validate_cards {
+            config.params.day4.validate = validate_cards;
+        }
+
+        if let Some(solve_seconds) = file.timeouts.solve_seconds {
+            if solve_seconds == 0 {
+                return Err(invalid(
+                    "timeouts.solve_seconds",
+                    "must be at least 1, leave it out for no timeout",
+                ));
+            }
+
+            config.timeout = Some(Duration::from_secs(solve_seconds));
+        }
+
+        if let Some(render) = file.output.render {
+            config.render_format = parse_variant("output.render", &render)?;
+        }
+
+        if let Some(explain) = file.output.explain {
+            config.explain_format = parse_variant("output.explain", &explain)?;
+        }
+
+        if let Some(cascade) = file.output.cascade {
+            config.cascade_format = parse_variant("output.cascade", &cascade)?;
+        }
+
+        config
+            .validate_current()
+            .map_err(|message| invalid("current", &message))?;
+
+        Ok(config)
+    }
+}
+
+impl Config {
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        fs::read_to_string(path)
+            .map_err(|err| Error::Read(format!("{}: {err}", path.display())))?
+            .parse()
+    }
+
+    /// Loads `path` if it exists, or else gives the defaults.
+    pub fn load_or_default(path: &Path) -> Result<Self, Error> {
+        if path.exists() {
+            Config::load(path)
+        } else {
+            Ok(Config::default())
+        }
+    }
+
+    /// Overrides the configuration with the environment variables that are set.
+    pub fn apply_env(&mut self) -> Result<(), Error> {
+        self.apply_vars(|var| std::env::var(var).ok())
+    }
+
+    fn apply_vars(&mut self, get_var: impl Fn(&str) -> Option<String>) -> Result<(), Error> {
+        if let Some(rundata_dir) = get_var("RUNDATA_DIR") {
+            self.rundata_dir = Some(PathBuf::from(rundata_dir));
+        }
+
+        if let Some(testdata_dir) = get_var("TESTDATA_DIR") {
+            self.testdata_dir = PathBuf::from(testdata_dir);
+        }
+
+        for (var, number) in [
+            ("CURRENT_DAY", &mut self.day),
+            ("CURRENT_PART", &mut self.part),
+        ] {
+            if let Some(value) = get_var(var) {
+                *number = Some(value.parse().map_err(|_| Error::Env {
+                    var: var.to_string(),
+                    message: format!("`{value}` is not a number from 0 to 255"),
+                })?);
+            }
+        }
+
+        self.validate_current().map_err(|message| Error::Env {
+            var: "CURRENT_DAY or CURRENT_PART".to_string(),
+            message,
+        })
+    }
+
+    /// The challenge that is run if no command is given.
+    pub fn current_challenge(&self) -> Option<Challenge> {
+        Challenge::from_day_part(self.day?, self.part?)
+    }
+
+    fn validate_current(&self) -> Result<(), String> {
+        match (self.day, self.part) {
+            (Some(day), Some(part)) if Challenge::from_day_part(day, part).is_none() => {
+                Err(format!("there is no challenge for day {day} part {part}"))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Strict parsing only accepts input in exactly the puzzle's format.
+pub fn parse_mode(strict: bool) -> ParseMode {
+    if strict {
+        ParseMode::Strict
+    } else {
+        ParseMode::Lenient
+    }
+}
+
+/// Parses the symbols that can be day 3 gears, e.g. "*#".
+pub fn parse_gear_symbols(s: &str) -> Result<Vec<char>, String> {
+    if s.is_empty() {
+        return Err("At least one symbol is needed".to_string());
+    }
+
+    if let Some(character) = s.chars().find(|&character| !day3::is_symbol(character)) {
+        return Err(format!(
+            "`{character}` is not a symbol, symbols are ASCII characters other than `.`, digits and letters"
+        ));
+    }
+
+    Ok(s.chars().collect())
+}
+
+/// Parses a range of how many numbers have to be adjacent to a day 3 gear, e.g. "2" or "2-4".
+pub fn parse_adjacent_numbers(s: &str) -> Result<RangeInclusive<usize>, String> {
+    let (min, max) = s.split_once('-').unwrap_or((s, s));
+
+    let min = min
+        .trim()
+        .parse()
+        .map_err(|_| format!("Invalid minimum: {min}"))?;
+    let max = max
+        .trim()
+        .parse()
+        .map_err(|_| format!("Invalid maximum: {max}"))?;
+
+    if min > max {
+        return Err(format!("Minimum {min} is larger than maximum {max}"));
+    }
+
+    Ok(min..=max)
+}
+
+/// Parses how far away day 3 cells can be to count as adjacent, at most [`Neighbourhood::MAX_RADIUS`].
+pub fn parse_neighbourhood_radius(s: &str) -> Result<usize, String> {
+    let radius: usize = s
+        .trim()
+        .parse()
+        .map_err(|_| format!("Invalid radius: {s}"))?;
+
+    check_neighbourhood_radius(radius)
+}
+
+fn check_neighbourhood_radius(radius: usize) -> Result<usize, String> {
+    if radius > Neighbourhood::MAX_RADIUS {
+        return Err(format!(
+            "Radius {radius} is larger than the maximum of {}",
+            Neighbourhood::MAX_RADIUS
+        ));
+    }
+
+    Ok(radius)
+}
+
+fn parse_variant<T: FromStr + VariantNames>(key: &str, value: &str) -> Result<T, Error> {
+    value.parse().map_err(|_| {
+        invalid(
+            key,
+            &format!("`{value}` is not one of {}", T::VARIANTS.join(", ")),
+        )
+    })
+}
+
+fn invalid(key: &str, message: &str) -> Error {
+    Error::Invalid {
+        key: key.to_string(),
+        message: message.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::challenge::day3::Combination;
+    use crate::challenge::day4::OutOfRange;
+    use crate::challenge::grid::NeighbourhoodShape;
+    use crate::challenge::parse::ParseMode;
+    use crate::config::{Config, Error, DEFAULT_PATH};
+    use std::path::{Path, PathBuf};
+    use std::time::Duration;
+
+    #[test]
+    fn repository_config() {
+        let config = Config::load(Path::new(DEFAULT_PATH)).unwrap();
+
+        assert_eq!(config.testdata_dir, PathBuf::from("testdata"));
+        assert_eq!(config.params, Default::default());
+    }
+
+    #[test]
+    fn values() {
+        let config: Config = "
+            [current]
+            day = 3
+            part = 2
+
+            [day3]
+            neighbourhood = 'von-neumann'
+            gear_adjacent_numbers = '2-4'
+            gear_combination = 'max'
+
+            [day4]
+            out_of_range = 'wrap'
+
+            [timeouts]
+            solve_seconds = 5
+        "
+        .parse()
+        .unwrap();
+
+        assert_eq!(config.day, Some(3));
+        assert_eq!(config.part, Some(2));
+        assert_eq!(
+            config.params.day3.neighbourhood.shape,
+            NeighbourhoodShape::VonNeumann
+        );
+        assert_eq!(config.params.day3.gear_rule.adjacent_numbers, 2..=4);
+        assert_eq!(config.params.day3.gear_rule.combination, Combination::Max);
+        assert_eq!(config.params.day4.out_of_range, OutOfRange::Wrap);
+        assert_eq!(config.timeout, Some(Duration::from_secs(5)));
+        assert_eq!("".parse::<Config>(), Ok(Config::default()));
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(matches!(
+            "[day3]\nneighborhood = 'moore'".parse::<Config>(),
+            Err(Error::Syntax(err)) if err.contains("unknown field `neighborhood`")
+        ));
+        assert_eq!(
+            "[day4]\nout_of_range = 'ignore'"
+                .parse::<Config>()
+                .map_err(|err| err.to_string()),
+            Err(
+                "invalid value for day4.out_of_range: `ignore` is not one of clamp, error, wrap"
+                    .to_string()
+            )
+        );
+        assert!(matches!(
+            "[day2]\nbag = '12 red,'".parse::<Config>(),
+            Err(Error::Invalid { key, .. }) if key == "day2.bag"
+        ));
+        assert!(matches!(
+            "[current]\nday = 30\npart = 1".parse::<Config>(),
+            Err(Error::Invalid { key, .. }) if key == "current"
+        ));
+
+        for (toml, key) in [
+            ("[day3]\ngear_symbols = ''", "day3.gear_symbols"),
+            ("[day3]\ngear_symbols = '*a'", "day3.gear_symbols"),
+            (
+                "[day3]\nneighbourhood_radius = 1001",
+                "day3.neighbourhood_radius",
+            ),
+        ] {
+            assert!(matches!(
+                toml.parse::<Config>(),
+                Err(Error::Invalid { key: invalid_key, .. }) if invalid_key == key
+            ));
+        }
+    }
+
+    #[test]
+    fn switch_off() {
+        let config: Config = "
+            [input]
+            strict = true
+
+            [day3]
+            wrap_around = true
+
+            [day4]
+            validate_cards = true
+        "
+        .parse()
+        .unwrap();
+
+        assert_eq!(config.params.parse_mode, ParseMode::Strict);
+        assert!(config.params.day3.neighbourhood.wrap_around);
+        assert!(config.params.day4.validate);
+
+        let config: Config = "[input]\nstrict = false\n[day3]\nwrap_around = false"
+            .parse()
+            .unwrap();
+
+        assert_eq!(config.params, Default::default());
+    }
+
+    #[test]
+    fn env_overrides_file() {
+        let mut config: Config = "[directories]\ntestdata = 'a'\n[current]\nday = 1\npart = 1"
+            .parse()
+            .unwrap();
+
+        config
+            .apply_vars(|var| match var {
+                "TESTDATA_DIR" => Some("b".to_string()),
+                "CURRENT_PART" => Some("2".to_string()),
+                _ => None,
+            })
+            .unwrap();
+
+        assert_eq!(config.testdata_dir, PathBuf::from("b"));
+        assert_eq!((config.day, config.part), (Some(1), Some(2)));
+
+        assert!(matches!(
+            config.apply_vars(|var| (var == "CURRENT_PART").then(|| "3".to_string())),
+            Err(Error::Env { .. })
+        ));
+    }
+}
@@ -0,0 +1,74 @@
+use serde::Deserialize;
+use std::path::PathBuf;
+
+/// The layout of `aoc.toml` before its values are validated.
+#[derive(Clone, Eq, PartialEq, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(super) struct File {
+    pub directories: Directories,
+    pub current: Current,
+    pub input: Input,
+    pub day2: Day2,
+    pub day3: Day3,
+    pub day4: Day4,
+    pub timeouts: Timeouts,
+    pub output: Output,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(super) struct Directories {
+    pub rundata: Option<PathBuf>,
+    pub testdata: Option<PathBuf>,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(super) struct Current {
+    pub day: Option<u8>,
+    pub part: Option<u8>,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(super) struct Input {
+    pub strict: Option<bool>,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(super) struct Day2 {
+    pub bag: Option<String>,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(super) struct Day3 {
+    pub neighbourhood: Option<String>,
+    pub neighbourhood_radius: Option<usize>,
+    pub wrap_around: Option<bool>,
+    pub gear_symbols: Option<String>,
+    pub gear_adjacent_numbers: Option<String>,
+    pub gear_combination: Option<String>,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(super) struct Day4 {
+    pub out_of_range: Option<String>,
+    pub validate_cards: Option<bool>,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(super) struct Timeouts {
+    pub solve_seconds: Option<u64>,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(super) struct Output {
+    pub render: Option<String>,
+    pub explain: Option<String>,
+    pub cascade: Option<String>,
+}
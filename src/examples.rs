@@ -0,0 +1,274 @@
+//! Extracts the examples from a saved puzzle description page.
+//!
+//! The page has an `<article>` per puzzle part. Example inputs are `<pre><code>` blocks,
+//! and the example answer is usually the last `<code><em>` in the part's description.
+
+use crate::rundata::read_if_exists;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// The examples in the description of one puzzle part.
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+pub struct PartExamples {
+    /// Contents of the `<pre><code>` blocks, without a trailing line break
+    pub blocks: Vec<String>,
+    /// Contents of the emphasised `<code><em>` elements, in order
+    pub answers: Vec<String>,
+}
+
+impl PartExamples {
+    /// The answer to the example, assuming it is the last emphasised code of the description.
+    pub fn answer(&self) -> Option<&str> {
+        self.answers.last().map(String::as_str)
+    }
+}
+
+/// The examples of every part described in `html`, in order.
+pub fn extract(html: &str) -> Vec<PartExamples> {
+    let articles = elements(html, "<article", "</article>");
+
+    if articles.is_empty() {
+        vec![extract_part(html)]
+    } else {
+        articles.into_iter().map(extract_part).collect()
+    }
+}
+
+/// The day from a title like `--- Day 4: Scratchcards ---`.
+pub fn day_number(html: &str) -> Option<u8> {
+    let (_, title) = html.split_once("--- Day ")?;
+    let (number, _) = title.split_once(':')?;
+
+    number.trim().parse().ok()
+}
+
+fn extract_part(html: &str) -> PartExamples {
+    let mut examples = PartExamples::default();
+    let mut rest = html;
+
+    while let Some(code_idx) = rest.find("<code>") {
+        let before = &rest[..code_idx];
+        let after = &rest[code_idx + "<code>".len()..];
+        let Some(end_idx) = after.find("</code>") else {
+            break;
+        };
+        let content = &after[..end_idx];
+
+        if before.trim_end().ends_with("<pre>") {
+            let block = decode(content);
+            examples
+                .blocks
+                .push(block.strip_suffix('\n').unwrap_or(&block).to_string());
+        } else if let Some(emphasised) = content
+            .strip_prefix("<em>")
+            .and_then(|content| content.strip_suffix("</em>"))
+        {
+            examples.answers.push(decode(emphasised));
+        }
+
+        rest = &after[end_idx + "</code>".len()..];
+    }
+
+    examples
+}
+
+/// The contents of every element that starts with `start` and ends with `end`,
+/// without the element's own tags.
+fn elements<'a>(html: &'a str, start: &str, end: &str) -> Vec<&'a str> {
+    let mut elements = Vec::new();
+    let mut rest = html;
+
+    while let Some(start_idx) = rest.find(start) {
+        let Some(tag_end_idx) = rest[start_idx..].find('>') else {
+            break;
+        };
+        let content = &rest[start_idx + tag_end_idx + 1..];
+        let Some(end_idx) = content.find(end) else {
+            break;
+        };
+
+        elements.push(&content[..end_idx]);
+        rest = &content[end_idx + end.len()..];
+    }
+
+    elements
+}
+
+/// Removes tags, e.g. highlighting inside an example, and replaces character references.
+fn decode(html: &str) -> String {
+    let mut text = String::new();
+    let mut rest = html;
+
+    while let Some(idx) = rest.find(['<', '&']) {
+        text.push_str(&rest[..idx]);
+        rest = &rest[idx..];
+
+        if rest.starts_with('<') {
+            rest = rest.find('>').map_or("", |end_idx| &rest[end_idx + 1..]);
+        } else {
+            let reference = rest
+                .find(';')
+                .and_then(|end_idx| Some((decode_reference(&rest[1..end_idx])?, end_idx)));
+
+            match reference {
+                Some((c, end_idx)) => {
+                    text.push(c);
+                    rest = &rest[end_idx + 1..];
+                }
+                None => {
+                    text.push('&');
+                    rest = &rest[1..];
+                }
+            }
+        }
+    }
+
+    text.push_str(rest);
+    text
+}
+
+fn decode_reference(name: &str) -> Option<char> {
+    match name {
+        "lt" => Some('<'),
+        "gt" => Some('>'),
+        "amp" => Some('&'),
+        "quot" => Some('"'),
+        "apos" => Some('\''),
+        _ => {
+            let number = name.strip_prefix('#')?;
+            let code = match number.strip_prefix(['x', 'X']) {
+                Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                None => number.parse().ok()?,
+            };
+
+            char::from_u32(code)
+        }
+    }
+}
+
+/// The directory of a test case written by [`write_test_case`].
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum TestCase {
+    Created(PathBuf),
+    /// A test case with the same input and output already existed, nothing was written
+    AlreadyExists(PathBuf),
+}
+
+/// Writes a new test case into the first free `test<K>` directory of the part in `testdata_dir`,
+/// unless one of the existing test cases is the same.
+pub fn write_test_case(
+    testdata_dir: &Path,
+    day: u8,
+    part: u8,
+    input: &str,
+    output: &str,
+) -> io::Result<TestCase> {
+    let part_dir_path = testdata_dir
+        .join(format!("day{day}"))
+        .join(format!("part{part}"));
+
+    for test_number in 1.. {
+        let test_dir_path = part_dir_path.join(format!("test{test_number}"));
+
+        if !test_dir_path.exists() {
+            fs::create_dir_all(&test_dir_path)?;
+            fs::write(test_dir_path.join("in.txt"), input)?;
+            fs::write(test_dir_path.join("out.txt"), output)?;
+
+            return Ok(TestCase::Created(test_dir_path));
+        }
+
+        if read_if_exists(&test_dir_path.join("in.txt"))?.as_deref() == Some(input)
+            && read_if_exists(&test_dir_path.join("out.txt"))?.as_deref() == Some(output)
+        {
+            return Ok(TestCase::AlreadyExists(test_dir_path));
+        }
+    }
+
+    unreachable!("Ran out of test numbers")
+}
+
+#[cfg(test)]
+mod test {
+    use crate::examples::{day_number, extract, write_test_case, PartExamples, TestCase};
+    use std::fs;
+
+    const PAGE: &str = r#"<main>
+<article class="day-desc"><h2>--- Day 3: Gear Ratios ---</h2>
+<p>Here is an example engine schematic:</p>
+<pre><code>467..114..
+...*......
+<em>35</em>..633.
+</code></pre>
+<p>Some numbers are <em>not</em> adjacent: <code>114</code> and <code>58</code>.
+In this schematic, the sum is <code><em>4361</em></code>.</p>
+</article>
+<article class="day-desc"><h2 id="part2">--- Part Two ---</h2>
+<pre><code>a &lt;b&gt; &amp;&#99;</code></pre>
+<p>The gear ratio is <code><em>16345</em></code>, and adding up all of them
+produces <code><em>467835</em></code>.</p>
+</article>
+</main>"#;
+
+    #[test]
+    fn puzzle_page() {
+        let parts = extract(PAGE);
+
+        assert_eq!(day_number(PAGE), Some(3));
+        assert_eq!(
+            parts,
+            vec![
+                PartExamples {
+                    blocks: vec!["467..114..\n...*......\n35..633.".to_string()],
+                    answers: vec!["4361".to_string()],
+                },
+                PartExamples {
+                    blocks: vec!["a <b> &c".to_string()],
+                    answers: vec!["16345".to_string(), "467835".to_string()],
+                },
+            ]
+        );
+        assert_eq!(parts[1].answer(), Some("467835"));
+    }
+
+    #[test]
+    fn test_cases() {
+        let testdata_dir =
+            std::env::temp_dir().join(format!("aoc-2023-examples-{}", std::process::id()));
+        _ = fs::remove_dir_all(&testdata_dir);
+        let test_dir_path = |test_number: usize| {
+            testdata_dir
+                .join("day3/part1")
+                .join(format!("test{test_number}"))
+        };
+
+        assert_eq!(
+            write_test_case(&testdata_dir, 3, 1, "1*", "1").unwrap(),
+            TestCase::Created(test_dir_path(1))
+        );
+        assert_eq!(
+            write_test_case(&testdata_dir, 3, 1, "1*", "1").unwrap(),
+            TestCase::AlreadyExists(test_dir_path(1))
+        );
+        assert_eq!(
+            write_test_case(&testdata_dir, 3, 1, "1*", "2").unwrap(),
+            TestCase::Created(test_dir_path(2))
+        );
+        assert_eq!(
+            fs::read_to_string(test_dir_path(2).join("out.txt")).unwrap(),
+            "2"
+        );
+
+        fs::remove_dir_all(&testdata_dir).unwrap();
+    }
+
+    #[test]
+    fn without_articles() {
+        let parts = extract("<pre><code>1 &amp; 2 & 3</code></pre><code><em>6</em></code>");
+
+        assert_eq!(parts.len(), 1);
+        assert_eq!(parts[0].blocks, vec!["1 & 2 & 3".to_string()]);
+        assert_eq!(parts[0].answer(), Some("6"));
+    }
+}
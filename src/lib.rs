@@ -0,0 +1,201 @@
+//! Solutions to Advent of Code 2023.
+//!
+//! [`Challenge`] lists every solved puzzle part,
+//! and the [`challenge`] module has the solvers and parsed puzzle inputs of every day.
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::fs;
+#[cfg(not(target_arch = "wasm32"))]
+use std::io;
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::Path;
+use strum::{EnumIter, IntoEnumIterator};
+
+pub mod challenge;
+// Modules that work with files, which WebAssembly builds don't have
+#[cfg(not(target_arch = "wasm32"))]
+pub mod config;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod examples;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod rundata;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod scaffold;
+#[cfg(feature = "server")]
+pub mod server;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+/// A test case from the test data directory whose output did not match.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct TestFailure {
+    pub challenge: Challenge,
+    pub input: String,
+    pub expected_output: String,
+    pub actual_output: String,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug, EnumIter)]
+pub enum Challenge {
+    Day1_1,
+    Day1_2,
+    Day2_1,
+    Day2_2,
+    Day3_1,
+    Day3_2,
+    Day4_1,
+    Day4_2,
+}
+
+impl Challenge {
+    pub fn from_day_part(day_number: u8, part_number: u8) -> Option<Challenge> {
+        Challenge::iter().find(|challenge| challenge.day_part_number() == (day_number, part_number))
+    }
+
+    pub fn day_part_number(&self) -> (u8, u8) {
+        match self {
+            Challenge::Day1_1 => (1, 1),
+            Challenge::Day1_2 => (1, 2),
+            Challenge::Day2_1 => (2, 1),
+            Challenge::Day2_2 => (2, 2),
+            Challenge::Day3_1 => (3, 1),
+            Challenge::Day3_2 => (3, 2),
+            Challenge::Day4_1 => (4, 1),
+            Challenge::Day4_2 => (4, 2),
+        }
+    }
+
+    pub fn run(&self, input: &str, params: &challenge::Params) -> Result<String, challenge::Error> {
+        match self {
+            Challenge::Day1_1 => challenge::day1::run_part_1(input),
+            Challenge::Day1_2 => challenge::day1::run_part_2(input),
+            Challenge::Day2_1 => {
+                challenge::day2::run_part_1(input, params.parse_mode, &params.day2)
+            }
+            Challenge::Day2_2 => {
+                challenge::day2::run_part_2(input, params.parse_mode, &params.day2)
+            }
+            Challenge::Day3_1 => {
+                challenge::day3::run_part_1(input, params.parse_mode, &params.day3)
+            }
+            Challenge::Day3_2 => {
+                challenge::day3::run_part_2(input, params.parse_mode, &params.day3)
+            }
+            Challenge::Day4_1 => {
+                challenge::day4::run_part_1(input, params.parse_mode, &params.day4)
+            }
+            Challenge::Day4_2 => {
+                challenge::day4::run_part_2(input, params.parse_mode, &params.day4)
+            }
+        }
+    }
+}
+
+/// Runs every challenge on its test cases in `base_path`,
+/// which has a `day<N>/part<M>/<test>/` directory with `in.txt` and `out.txt` for every test case.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn run_tests(base_path: &Path) -> Result<(), Vec<TestFailure>> {
+    let mut failures = Vec::new();
+
+    for challenge in Challenge::iter() {
+        let (_test_count, challenge_failures) =
+            test_challenge(challenge, base_path).expect("Could not read test cases");
+
+        failures.extend(challenge_failures);
+    }
+
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        Err(failures)
+    }
+}
+
+/// Runs `challenge` on its test cases in `base_path`, see [`run_tests`].
+/// Gives the number of test cases together with the failures.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn test_challenge(
+    challenge: Challenge,
+    base_path: &Path,
+) -> io::Result<(usize, Vec<TestFailure>)> {
+    let (day_number, part_number) = challenge.day_part_number();
+
+    let part_dir_path = base_path
+        .join(format!("day{day_number}"))
+        .join(format!("part{part_number}"));
+
+    let mut test_count = 0;
+    let mut failures = Vec::new();
+
+    for day_test_dir in part_dir_path.read_dir()? {
+        let day_test_dir_path = day_test_dir?.path();
+
+        let input = fs::read_to_string(day_test_dir_path.join("in.txt"))?;
+        let expected_output = fs::read_to_string(day_test_dir_path.join("out.txt"))?;
+
+        let actual_output = challenge
+            .run(&input, &challenge::Params::default())
+            .unwrap_or_else(|err| format!("Error: {err}"));
+
+        test_count += 1;
+
+        if actual_output != expected_output {
+            failures.push(TestFailure {
+                challenge,
+                input,
+                expected_output,
+                actual_output,
+            });
+        }
+    }
+
+    Ok((test_count, failures))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::challenge::parse::ParseMode;
+    use crate::config::{self, Config};
+    use crate::{challenge, Challenge, TestFailure};
+    use std::path::Path;
+    use strum::IntoEnumIterator;
+
+    #[test]
+    fn run_tests() -> Result<(), Vec<TestFailure>> {
+        _ = dotenv::dotenv();
+
+        let mut config =
+            Config::load_or_default(Path::new(config::DEFAULT_PATH)).expect("Invalid aoc.toml");
+        config.apply_env().expect("Invalid environment");
+
+        crate::run_tests(&config.testdata_dir)
+    }
+
+    #[test]
+    fn generated_inputs() {
+        let mut params = challenge::Params {
+            parse_mode: ParseMode::Strict,
+            ..challenge::Params::default()
+        };
+        params.day4.validate = true;
+
+        for challenge in Challenge::iter() {
+            let (day_number, _part_number) = challenge.day_part_number();
+
+            for seed in 0..10 {
+                // Days without a generator yet, e.g. freshly scaffolded ones, are skipped
+                let Some(input) = challenge::generate(day_number, seed, 50) else {
+                    break;
+                };
+                assert_eq!(
+                    challenge::generate(day_number, seed, 50),
+                    Some(input.clone())
+                );
+
+                if let Err(err) = challenge.run(&input, &params) {
+                    panic!("{challenge:?} failed on seed {seed}: {err}\n{input}");
+                }
+            }
+        }
+    }
+}
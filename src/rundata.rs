@@ -0,0 +1,146 @@
+//! The run data directory, which has a `day<N>/part<M>/` directory for every challenge
+//! with the puzzle input in `in.txt`, an `out_<timestamp>` file for every answer,
+//! a `verdict_<timestamp>` file for every answer that was submitted,
+//! and a `runtime_<timestamp>` file with the seconds it took to find the answer, if measured.
+
+use crate::Challenge;
+use chrono::{DateTime, SecondsFormat, Utc};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use strum::{Display, EnumString};
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Display, EnumString)]
+#[strum(serialize_all = "kebab-case")]
+pub enum Verdict {
+    Correct,
+    Wrong,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct SavedAnswer {
+    /// UTC time in RFC 3339 format, so that answers sort by time
+    pub timestamp: String,
+    pub answer: String,
+    pub verdict: Option<Verdict>,
+    pub runtime: Option<Duration>,
+}
+
+pub fn part_dir_path(rundata_dir: &Path, challenge: Challenge) -> PathBuf {
+    let (day_number, part_number) = challenge.day_part_number();
+
+    rundata_dir
+        .join(format!("day{day_number}"))
+        .join(format!("part{part_number}"))
+}
+
+pub fn read_input(part_dir_path: &Path) -> io::Result<String> {
+    fs::read_to_string(part_dir_path.join("in.txt"))
+}
+
+/// Saves `answer` with the current time, giving its timestamp.
+pub fn save_answer(part_dir_path: &Path, answer: &str) -> io::Result<String> {
+    let datetime = DateTime::<Utc>::from(SystemTime::now());
+    let timestamp = datetime.to_rfc3339_opts(SecondsFormat::Secs, true);
+
+    fs::write(part_dir_path.join(format!("out_{timestamp}")), answer)?;
+
+    Ok(timestamp)
+}
+
+pub fn save_verdict(part_dir_path: &Path, timestamp: &str, verdict: Verdict) -> io::Result<()> {
+    fs::write(
+        part_dir_path.join(format!("verdict_{timestamp}")),
+        verdict.to_string(),
+    )
+}
+
+pub fn save_runtime(part_dir_path: &Path, timestamp: &str, runtime: Duration) -> io::Result<()> {
+    fs::write(
+        part_dir_path.join(format!("runtime_{timestamp}")),
+        format!("{}.{:09}", runtime.as_secs(), runtime.subsec_nanos()),
+    )
+}
+
+/// Every saved answer, oldest first. Empty if the directory does not exist.
+pub fn history(part_dir_path: &Path) -> io::Result<Vec<SavedAnswer>> {
+    if !part_dir_path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut answers = Vec::new();
+
+    for entry in part_dir_path.read_dir()? {
+        let file_name = entry?.file_name();
+        let Some(timestamp) = file_name
+            .to_str()
+            .and_then(|name| name.strip_prefix("out_"))
+        else {
+            continue;
+        };
+
+        let verdict = read_if_exists(&part_dir_path.join(format!("verdict_{timestamp}")))?
+            .and_then(|verdict| verdict.trim().parse().ok());
+        let runtime = read_if_exists(&part_dir_path.join(format!("runtime_{timestamp}")))?
+            .and_then(|runtime| runtime.trim().parse().ok())
+            .and_then(|seconds| Duration::try_from_secs_f64(seconds).ok());
+
+        answers.push(SavedAnswer {
+            timestamp: timestamp.to_string(),
+            answer: fs::read_to_string(part_dir_path.join(&file_name))?,
+            verdict,
+            runtime,
+        });
+    }
+
+    answers.sort_by(|answer1, answer2| answer1.timestamp.cmp(&answer2.timestamp));
+
+    Ok(answers)
+}
+
+/// Reads a file that may not have been written yet.
+pub(crate) fn read_if_exists(path: &Path) -> io::Result<Option<String>> {
+    match fs::read_to_string(path) {
+        Ok(contents) => Ok(Some(contents)),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::rundata::{history, save_answer, save_runtime, save_verdict, Verdict};
+    use std::fs;
+    use std::time::Duration;
+
+    #[test]
+    fn answers_and_verdicts() {
+        let part_dir_path =
+            std::env::temp_dir().join(format!("aoc-2023-rundata-{}", std::process::id()));
+        _ = fs::remove_dir_all(&part_dir_path);
+
+        assert_eq!(history(&part_dir_path).unwrap(), Vec::new());
+
+        fs::create_dir_all(&part_dir_path).unwrap();
+        fs::write(part_dir_path.join("in.txt"), "input").unwrap();
+        fs::write(part_dir_path.join("out_2023-12-01T05:00:00Z"), "1").unwrap();
+        let timestamp = save_answer(&part_dir_path, "2").unwrap();
+        save_verdict(&part_dir_path, &timestamp, Verdict::Wrong).unwrap();
+        save_runtime(&part_dir_path, &timestamp, Duration::from_micros(1500)).unwrap();
+
+        let answers = history(&part_dir_path).unwrap();
+        assert_eq!(
+            answers
+                .iter()
+                .map(|answer| (answer.answer.as_str(), answer.verdict, answer.runtime))
+                .collect::<Vec<_>>(),
+            vec![
+                ("1", None, None),
+                ("2", Some(Verdict::Wrong), Some(Duration::from_micros(1500)))
+            ]
+        );
+
+        fs::remove_dir_all(&part_dir_path).unwrap();
+    }
+}
@@ -0,0 +1,235 @@
+//! Creates the files for a new day and registers it as a [`crate::Challenge`].
+
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const DAY_TEMPLATE: &str = include_str!("scaffold/day.rs.template");
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum Error {
+    InvalidDay(u8),
+    /// The day already exists, nothing was changed
+    AlreadyExists(PathBuf),
+    /// A source file that has to be changed does not look as expected, nothing was changed
+    UnexpectedSource(PathBuf),
+    Io(String),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::InvalidDay(day) => write!(f, "day {day} is not between 1 and 25"),
+            Error::AlreadyExists(path) => write!(f, "{} already exists", path.display()),
+            Error::UnexpectedSource(path) => {
+                write!(
+                    f,
+                    "could not find where to add the day in {}",
+                    path.display()
+                )
+            }
+            Error::Io(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// What [`new_day`] did.
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+pub struct Changes {
+    pub created: Vec<PathBuf>,
+    pub modified: Vec<PathBuf>,
+}
+
+/// Adds `day` to the crate in `root`: a solver module with stubbed parts and a parse error,
+/// its `Challenge` variants and an empty test case for both parts.
+///
+/// Refuses to overwrite anything, and checks every file before changing the first one.
+pub fn new_day(root: &Path, day: u8) -> Result<Changes, Error> {
+    if !(1..=25).contains(&day) {
+        return Err(Error::InvalidDay(day));
+    }
+
+    let module_path = root.join("src/challenge").join(format!("day{day}.rs"));
+    let testdata_path = root.join("testdata").join(format!("day{day}"));
+    let challenge_path = root.join("src/challenge.rs");
+    let lib_path = root.join("src/lib.rs");
+
+    for path in [&module_path, &testdata_path] {
+        if path.exists() {
+            return Err(Error::AlreadyExists(path.clone()));
+        }
+    }
+
+    let challenge_source = read(&challenge_path)?;
+    let lib_source = read(&lib_path)?;
+
+    if lib_source.contains(&format!("Day{day}_1")) {
+        return Err(Error::AlreadyExists(lib_path));
+    }
+
+    let challenge_source = add_module(&challenge_source, day)
+        .ok_or_else(|| Error::UnexpectedSource(challenge_path.clone()))?;
+    let lib_source = add_challenges(&lib_source, day)
+        .ok_or_else(|| Error::UnexpectedSource(lib_path.clone()))?;
+
+    let mut changes = Changes::default();
+
+    write(
+        &module_path,
+        &DAY_TEMPLATE.replace("{day}", &day.to_string()),
+    )?;
+    changes.created.push(module_path);
+
+    for part in 1..=2 {
+        let test_path = testdata_path.join(format!("part{part}")).join("test1");
+        fs::create_dir_all(&test_path).map_err(|err| Error::Io(err.to_string()))?;
+
+        for file_name in ["in.txt", "out.txt"] {
+            write(&test_path.join(file_name), "")?;
+            changes.created.push(test_path.join(file_name));
+        }
+    }
+
+    write(&challenge_path, &challenge_source)?;
+    changes.modified.push(challenge_path);
+    write(&lib_path, &lib_source)?;
+    changes.modified.push(lib_path);
+
+    Ok(changes)
+}
+
+fn read(path: &Path) -> Result<String, Error> {
+    fs::read_to_string(path).map_err(|err| Error::Io(format!("{}: {err}", path.display())))
+}
+
+fn write(path: &Path, contents: &str) -> Result<(), Error> {
+    fs::write(path, contents).map_err(|err| Error::Io(format!("{}: {err}", path.display())))
+}
+
+/// Declares the module after the last day module in `src/challenge.rs`.
+fn add_module(source: &str, day: u8) -> Option<String> {
+    let mut lines: Vec<&str> = source.lines().collect();
+
+    let last_day_idx = lines
+        .iter()
+        .rposition(|line| line.starts_with("pub mod day"))?;
+
+    let declaration = format!("pub mod day{day};");
+    lines.insert(last_day_idx + 1, &declaration);
+
+    Some(lines.join("\n") + "\n")
+}
+
+/// Adds the variants to `Challenge` and arms for them after the last arms of its methods.
+fn add_challenges(source: &str, day: u8) -> Option<String> {
+    let mut lines: Vec<String> = source.lines().map(str::to_string).collect();
+
+    let enum_idx = lines
+        .iter()
+        .position(|line| line == "pub enum Challenge {")?;
+    let enum_end_idx = enum_idx + lines[enum_idx..].iter().position(|line| line == "}")?;
+    lines.splice(
+        enum_end_idx..enum_end_idx,
+        [format!("    Day{day}_1,"), format!("    Day{day}_2,")],
+    );
+
+    insert_arms(&mut lines, "pub fn day_part_number(", |indent, part| {
+        format!("{indent}Challenge::Day{day}_{part} => ({day}, {part}),")
+    })?;
+
+    insert_arms(&mut lines, "pub fn run(", |indent, part| {
+        format!(
+            "{indent}Challenge::Day{day}_{part} => challenge::day{day}::run_part_{part}(input, params.parse_mode),"
+        )
+    })?;
+
+    Some(lines.join("\n") + "\n")
+}
+
+/// Inserts an arm for both parts after the last `Challenge::Day<N>_2` arm of the method
+/// whose signature starts with `signature`.
+fn insert_arms(
+    lines: &mut Vec<String>,
+    signature: &str,
+    arm: impl Fn(&str, u8) -> String,
+) -> Option<()> {
+    let method_idx = lines
+        .iter()
+        .position(|line| line.trim_start().starts_with(signature))?;
+    let method_end_idx = method_idx
+        + lines[method_idx..]
+            .iter()
+            .position(|line| line == "    }")?;
+
+    let last_arm_idx = (method_idx..method_end_idx).rev().find(|&idx| {
+        let line = lines[idx].trim_start();
+        line.starts_with("Challenge::Day") && line.contains("_2 =>")
+    })?;
+
+    let indent_len = lines[last_arm_idx].len() - lines[last_arm_idx].trim_start().len();
+    let indent = lines[last_arm_idx][..indent_len].to_string();
+
+    // Arms with a block end at the closing brace with the same indentation
+    let last_arm_end_idx = if lines[last_arm_idx].ends_with('{') {
+        last_arm_idx
+            + lines[last_arm_idx..method_end_idx]
+                .iter()
+                .position(|line| {
+                    line.strip_prefix(&indent)
+                        .is_some_and(|rest| rest.starts_with('}'))
+                })?
+    } else {
+        last_arm_idx
+    };
+
+    lines.splice(
+        last_arm_end_idx + 1..last_arm_end_idx + 1,
+        [arm(&indent, 1), arm(&indent, 2)],
+    );
+
+    Some(())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::scaffold::{new_day, Error};
+    use std::fs;
+    use std::path::Path;
+
+    #[test]
+    fn new_day_in_copy_of_crate() {
+        let root = std::env::temp_dir().join(format!("aoc-2023-scaffold-{}", std::process::id()));
+        _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("src/challenge")).unwrap();
+
+        for path in ["src/lib.rs", "src/challenge.rs"] {
+            fs::copy(Path::new(path), root.join(path)).unwrap();
+        }
+
+        let changes = new_day(&root, 25).unwrap();
+        assert_eq!(changes.created.len(), 5);
+
+        let lib_source = fs::read_to_string(root.join("src/lib.rs")).unwrap();
+        assert!(lib_source.contains("    Day25_2,\n}"));
+        assert!(lib_source.contains("Challenge::Day25_1 => (25, 1),"));
+        assert!(lib_source.contains(
+            "Challenge::Day25_2 => challenge::day25::run_part_2(input, params.parse_mode),"
+        ));
+
+        let challenge_source = fs::read_to_string(root.join("src/challenge.rs")).unwrap();
+        assert!(challenge_source.contains("pub mod day25;\n"));
+
+        assert_eq!(
+            new_day(&root, 25),
+            Err(Error::AlreadyExists(root.join("src/challenge/day25.rs")))
+        );
+        assert_eq!(
+            fs::read_to_string(root.join("src/lib.rs")).unwrap(),
+            lib_source
+        );
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
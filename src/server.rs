@@ -0,0 +1,333 @@
+//! A local HTTP server that exposes the solvers to tools that don't link Rust.
+//!
+//! - `GET /challenges` lists every challenge, e.g. `[{"day": 1, "part": 1}, ...]`.
+//! - `POST /solve/{day}/{part}` solves the puzzle input in the request body and responds with
+//!   `{"answer": "142", "runtime_ms": 0.02}`, or with `{"error": "..."}` if it can't.
+//!
+//! Any origin may call the API from a browser. Requests are served by [`WORKERS`] threads,
+//! and at most [`MAX_SOLVES`] challenges run at once, including ones that timed out
+//! but haven't finished yet.
+
+use crate::{challenge, Challenge};
+use serde_json::{json, Value};
+use std::io::{self, Read};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+use strum::IntoEnumIterator;
+
+/// Larger request bodies are rejected, puzzle inputs are much smaller.
+pub const MAX_BODY_BYTES: u64 = 16 * 1024 * 1024;
+
+/// Threads that serve requests.
+pub const WORKERS: usize = 4;
+
+/// Solves that may run at once, more are rejected until one finishes.
+pub const MAX_SOLVES: usize = 8;
+
+static RUNNING_SOLVES: AtomicUsize = AtomicUsize::new(0);
+
+/// Permission to run a solve, which is given back when dropped.
+struct SolveSlot;
+
+impl SolveSlot {
+    fn acquire() -> Option<Self> {
+        RUNNING_SOLVES
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |running| {
+                (running < MAX_SOLVES).then_some(running + 1)
+            })
+            .ok()
+            .map(|_| SolveSlot)
+    }
+}
+
+impl Drop for SolveSlot {
+    fn drop(&mut self) {
+        RUNNING_SOLVES.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct Response {
+    pub status: u16,
+    /// `Null` for responses without a body
+    pub body: Value,
+}
+
+impl Response {
+    fn error(status: u16, message: impl Into<String>) -> Self {
+        Response {
+            status,
+            body: json!({ "error": message.into() }),
+        }
+    }
+}
+
+/// Answers a request for `path`, which may end in a query string, without any I/O.
+/// Solving gives up after `timeout`, if there is one.
+pub fn handle(
+    method: &str,
+    path: &str,
+    body: &[u8],
+    params: &challenge::Params,
+    timeout: Option<Duration>,
+) -> Response {
+    let path = path.split_once('?').map_or(path, |(path, _query)| path);
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+
+    match (method, segments.as_slice()) {
+        ("GET", ["challenges"]) => Response {
+            status: 200,
+            body: Challenge::iter()
+                .map(|challenge| {
+                    let (day, part) = challenge.day_part_number();
+                    json!({ "day": day, "part": part })
+                })
+                .collect(),
+        },
+        ("POST", ["solve", day, part]) => solve(day, part, body, params, timeout),
+        // CORS preflight, the allowed methods and headers are sent by `serve`
+        ("OPTIONS", ["challenges"] | ["solve", _, _]) => Response {
+            status: 204,
+            body: Value::Null,
+        },
+        (_, ["challenges"] | ["solve", _, _]) => Response::error(405, "method not allowed"),
+        _ => Response::error(404, "not found"),
+    }
+}
+
+fn solve(
+    day: &str,
+    part: &str,
+    body: &[u8],
+    params: &challenge::Params,
+    timeout: Option<Duration>,
+) -> Response {
+    let challenge = day
+        .parse()
+        .ok()
+        .zip(part.parse().ok())
+        .and_then(|(day, part)| Challenge::from_day_part(day, part));
+
+    let Some(challenge) = challenge else {
+        return Response::error(
+            404,
+            format!("there is no challenge for day {day} part {part}"),
+        );
+    };
+
+    let Ok(input) = std::str::from_utf8(body) else {
+        return Response::error(400, "input must be UTF-8");
+    };
+
+    let input = input.to_string();
+    let params = params.clone();
+
+    run_with_timeout(timeout, move || challenge.run(&input, &params))
+}
+
+/// Runs `solve` and responds with its answer, or with an error if it doesn't finish within `timeout`.
+fn run_with_timeout(
+    timeout: Option<Duration>,
+    solve: impl FnOnce() -> Result<String, challenge::Error> + Send + 'static,
+) -> Response {
+    let Some(slot) = SolveSlot::acquire() else {
+        return Response::error(503, "too many challenges are running, try again later");
+    };
+
+    let start = Instant::now();
+
+    let answer = match timeout {
+        None => {
+            let answer = solve();
+            drop(slot);
+            answer
+        }
+        Some(timeout) => {
+            let (sender, receiver) = mpsc::channel();
+
+            // The slot is only given back when the solve finishes, even after a timeout
+            thread::spawn(move || {
+                let answer = solve();
+                drop(slot);
+                _ = sender.send(answer);
+            });
+
+            match receiver.recv_timeout(timeout) {
+                Ok(answer) => answer,
+                Err(RecvTimeoutError::Timeout) => {
+                    return Response::error(
+                        503,
+                        format!(
+                            "challenge did not finish within {} seconds",
+                            timeout.as_secs_f64()
+                        ),
+                    );
+                }
+                Err(RecvTimeoutError::Disconnected) => {
+                    return Response::error(500, "challenge panicked");
+                }
+            }
+        }
+    };
+
+    let runtime_ms = start.elapsed().as_secs_f64() * 1000.0;
+
+    match answer {
+        Ok(answer) => Response {
+            status: 200,
+            body: json!({ "answer": answer, "runtime_ms": runtime_ms }),
+        },
+        Err(err) => Response {
+            status: 422,
+            body: json!({ "error": err.to_string(), "runtime_ms": runtime_ms }),
+        },
+    }
+}
+
+/// Serves requests on `address`, e.g. `127.0.0.1:8080`, until the process ends.
+/// Requests are handled by a pool of [`WORKERS`] threads, so a slow solve doesn't hold up
+/// other clients.
+pub fn serve(
+    address: &str,
+    params: &challenge::Params,
+    timeout: Option<Duration>,
+) -> io::Result<()> {
+    let server = Arc::new(tiny_http::Server::http(address).map_err(io::Error::other)?);
+    let params = Arc::new(params.clone());
+
+    let workers: Vec<_> = (0..WORKERS)
+        .map(|_| {
+            let server = Arc::clone(&server);
+            let params = Arc::clone(&params);
+
+            thread::spawn(move || {
+                for request in server.incoming_requests() {
+                    respond(request, &params, timeout);
+                }
+            })
+        })
+        .collect();
+
+    for worker in workers {
+        worker.join().expect("Server worker panicked");
+    }
+
+    Ok(())
+}
+
+fn respond(mut request: tiny_http::Request, params: &challenge::Params, timeout: Option<Duration>) {
+    let mut body = Vec::new();
+
+    let response = if request
+        .body_length()
+        .is_some_and(|length| length as u64 > MAX_BODY_BYTES)
+    {
+        body_too_large()
+    } else {
+        // Without a Content-Length, the body could still be too large
+        match request
+            .as_reader()
+            .take(MAX_BODY_BYTES + 1)
+            .read_to_end(&mut body)
+        {
+            Ok(length) if length as u64 > MAX_BODY_BYTES => body_too_large(),
+            Ok(_) => handle(
+                request.method().as_str(),
+                request.url(),
+                &body,
+                params,
+                timeout,
+            ),
+            Err(err) => Response::error(400, format!("could not read request: {err}")),
+        }
+    };
+
+    let body = match response.body {
+        Value::Null => String::new(),
+        body => body.to_string(),
+    };
+
+    let headers = [
+        ("Content-Type", "application/json"),
+        ("Access-Control-Allow-Origin", "*"),
+        ("Access-Control-Allow-Methods", "GET, POST, OPTIONS"),
+        ("Access-Control-Allow-Headers", "Content-Type"),
+    ];
+
+    let mut http_response =
+        tiny_http::Response::from_string(body).with_status_code(response.status);
+
+    for (field, value) in headers {
+        http_response
+            .add_header(tiny_http::Header::from_bytes(field, value).expect("Header is valid"));
+    }
+
+    // A client that hung up doesn't stop the server
+    _ = request.respond(http_response);
+}
+
+fn body_too_large() -> Response {
+    Response::error(
+        413,
+        format!("request body must be at most {MAX_BODY_BYTES} bytes"),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use crate::challenge;
+    use crate::server::{handle, run_with_timeout};
+    use serde_json::{json, Value};
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    #[test]
+    fn routes() {
+        let params = challenge::Params::default();
+        let handle = |method, path, body| handle(method, path, body, &params, None);
+
+        let challenges = handle("GET", "/challenges", b"");
+        assert_eq!(challenges.status, 200);
+        assert_eq!(challenges.body[0], json!({ "day": 1, "part": 1 }));
+
+        let solved = handle("POST", "/solve/1/1", b"1abc2\npqr3stu8vwx");
+        assert_eq!(solved.status, 200);
+        assert_eq!(solved.body["answer"], json!("50"));
+        assert!(solved.body["runtime_ms"].is_f64());
+
+        assert_eq!(handle("POST", "/solve/1/1", b"abc").status, 422);
+        assert_eq!(handle("POST", "/solve/1/3", b"").status, 404);
+        assert_eq!(handle("POST", "/solve/1/1", &[0xff]).status, 400);
+        assert_eq!(handle("GET", "/solve/1/1", b"").status, 405);
+        assert_eq!(handle("GET", "/", b"").status, 404);
+
+        let preflight = handle("OPTIONS", "/solve/1/1", b"");
+        assert_eq!((preflight.status, preflight.body), (204, Value::Null));
+    }
+
+    #[test]
+    fn timeout() {
+        let params = challenge::Params::default();
+        let (sender, receiver) = mpsc::channel::<()>();
+
+        // Blocks until the sender is dropped, long after the deadline
+        let timed_out = run_with_timeout(Some(Duration::from_millis(10)), move || {
+            _ = receiver.recv();
+            Ok("too late".to_string())
+        });
+        assert_eq!(timed_out.status, 503);
+        drop(sender);
+
+        let solved = handle(
+            "POST",
+            "/solve/1/1",
+            b"1abc2",
+            &params,
+            Some(Duration::from_secs(60)),
+        );
+        assert_eq!(solved.body["answer"], json!("12"));
+    }
+}
@@ -0,0 +1,590 @@
+use aoc_2023::config::Config;
+use aoc_2023::rundata::{self, SavedAnswer, Verdict};
+use aoc_2023::Challenge;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Clear, List, Row, Table, TableState};
+use ratatui::{DefaultTerminal, Frame};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, TryRecvError};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+use strum::IntoEnumIterator;
+
+const BENCHMARK_RUNS: u32 = 10;
+
+/// How often the dashboard checks on a running job while waiting for keys.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+const HELP: &str = "↑/↓ select  r run  t test  a test all  b benchmark  h history  \
+                    c/w mark last answer correct/wrong  Esc cancel  q quit";
+
+/// Number of passed and of all test cases, or why they could not be run.
+type TestResult = Result<(usize, usize), String>;
+
+struct ChallengeStatus {
+    challenge: Challenge,
+    tests: Option<TestResult>,
+    answers: Vec<SavedAnswer>,
+    /// Fastest and mean runtime
+    benchmark: Option<(Duration, Duration)>,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+enum JobKind {
+    Run,
+    Benchmark,
+    Test,
+    TestAll,
+}
+
+impl JobKind {
+    /// How many times the job gets the full timeout.
+    fn runs(self) -> u32 {
+        match self {
+            JobKind::Run | JobKind::Test => 1,
+            JobKind::Benchmark => BENCHMARK_RUNS,
+            JobKind::TestAll => Challenge::iter().count() as u32,
+        }
+    }
+}
+
+enum JobOutput {
+    /// The last answer and the runtime of every run
+    Answers(String, Vec<Duration>),
+    /// The test results of every tested challenge, by index
+    Tests(Vec<(usize, TestResult)>),
+}
+
+/// What the job found, or why the challenge could not be solved.
+type JobResult = Result<JobOutput, String>;
+
+/// Solves or tests on the worker thread, gives no result if cancelled.
+type Work = Box<dyn FnOnce(&AtomicBool) -> Option<JobResult> + Send>;
+
+/// A challenge that is solved or tested on a worker thread, so that the dashboard stays responsive.
+struct Job {
+    idx: usize,
+    kind: JobKind,
+    started: Instant,
+    receiver: mpsc::Receiver<JobResult>,
+    /// Stops a benchmark before its next run. A run that already started can't be stopped,
+    /// it finishes in the background and its answer is dropped.
+    cancelled: Arc<AtomicBool>,
+}
+
+struct Dashboard {
+    config: Config,
+    statuses: Vec<ChallengeStatus>,
+    table_state: TableState,
+    show_history: bool,
+    message: String,
+    job: Option<Job>,
+}
+
+/// Shows every challenge with its test results, answers and runtimes until the user quits.
+pub fn run(config: Config) -> io::Result<()> {
+    let mut dashboard = Dashboard::new(config);
+
+    let mut terminal = ratatui::init();
+    let result = dashboard.event_loop(&mut terminal);
+    ratatui::restore();
+
+    result
+}
+
+impl Dashboard {
+    fn new(config: Config) -> Self {
+        let mut dashboard = Dashboard {
+            config,
+            statuses: Challenge::iter()
+                .map(|challenge| ChallengeStatus {
+                    challenge,
+                    tests: None,
+                    answers: Vec::new(),
+                    benchmark: None,
+                })
+                .collect(),
+            table_state: TableState::default(),
+            show_history: false,
+            message: String::new(),
+            job: None,
+        };
+
+        let current_idx = dashboard
+            .config
+            .current_challenge()
+            .and_then(|current| Challenge::iter().position(|challenge| challenge == current));
+        dashboard.table_state.select(Some(current_idx.unwrap_or(0)));
+
+        for idx in 0..dashboard.statuses.len() {
+            if let Err(err) = dashboard.load_answers(idx) {
+                dashboard.message = err;
+            }
+        }
+
+        dashboard
+    }
+
+    fn event_loop(&mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
+        loop {
+            terminal.draw(|frame| self.draw(frame))?;
+
+            if self.job.is_some() {
+                if let Err(err) = self.finish_job() {
+                    self.message = err;
+                }
+
+                // Redraw regularly to show the job's progress
+                if !event::poll(POLL_INTERVAL)? {
+                    continue;
+                }
+            }
+
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+
+            let idx = self.selected_idx();
+
+            let result = match key.code {
+                KeyCode::Char('q') => {
+                    self.cancel_job();
+                    return Ok(());
+                }
+                KeyCode::Esc if self.job.is_some() => {
+                    self.cancel_job();
+                    self.message = "Cancelled".to_string();
+                    Ok(())
+                }
+                KeyCode::Esc if self.show_history => {
+                    self.show_history = false;
+                    Ok(())
+                }
+                KeyCode::Esc => return Ok(()),
+                KeyCode::Up | KeyCode::Char('k') => {
+                    self.table_state.select(Some(idx.saturating_sub(1)));
+                    Ok(())
+                }
+                KeyCode::Down | KeyCode::Char('j') => {
+                    self.table_state
+                        .select(Some((idx + 1).min(self.statuses.len() - 1)));
+                    Ok(())
+                }
+                KeyCode::Char('r') => self.start_job(JobKind::Run),
+                KeyCode::Char('t') => self.start_job(JobKind::Test),
+                KeyCode::Char('a') => self.start_job(JobKind::TestAll),
+                KeyCode::Char('b') => self.start_job(JobKind::Benchmark),
+                KeyCode::Char('h') | KeyCode::Enter => {
+                    self.show_history = !self.show_history;
+                    Ok(())
+                }
+                KeyCode::Char('c') => self.mark_last_answer(Verdict::Correct),
+                KeyCode::Char('w') => self.mark_last_answer(Verdict::Wrong),
+                _ => Ok(()),
+            };
+
+            if let Err(err) = result {
+                self.message = err;
+            }
+        }
+    }
+
+    fn selected_idx(&self) -> usize {
+        self.table_state.selected().unwrap_or(0)
+    }
+
+    fn part_dir_path(&self, idx: usize) -> Result<PathBuf, String> {
+        let rundata_dir = self.config.rundata_dir.as_ref().ok_or(
+            "Set directories.rundata in aoc.toml, or the RUNDATA_DIR environment variable",
+        )?;
+
+        Ok(rundata::part_dir_path(
+            rundata_dir,
+            self.statuses[idx].challenge,
+        ))
+    }
+
+    fn load_answers(&mut self, idx: usize) -> Result<(), String> {
+        let Some(rundata_dir) = &self.config.rundata_dir else {
+            return Ok(());
+        };
+
+        let part_dir_path = rundata::part_dir_path(rundata_dir, self.statuses[idx].challenge);
+        self.statuses[idx].answers = rundata::history(&part_dir_path)
+            .map_err(|err| format!("Could not read {}: {err}", part_dir_path.display()))?;
+
+        Ok(())
+    }
+
+    fn read_input(&self, idx: usize) -> Result<(PathBuf, String), String> {
+        let part_dir_path = self.part_dir_path(idx)?;
+        let input = rundata::read_input(&part_dir_path)
+            .map_err(|err| format!("Could not read in file: {err}"))?;
+
+        Ok((part_dir_path, input))
+    }
+
+    /// Starts solving or testing the selected challenge, or testing all of them,
+    /// on a worker thread.
+    fn start_job(&mut self, kind: JobKind) -> Result<(), String> {
+        if self.job.is_some() {
+            return Err("Wait for the running challenge to finish, or press Esc".to_string());
+        }
+
+        let idx = self.selected_idx();
+        let challenge = self.statuses[idx].challenge;
+
+        let work: Work = match kind {
+            JobKind::Run | JobKind::Benchmark => {
+                let (_part_dir_path, input) = self.read_input(idx)?;
+                let params = self.config.params.clone();
+
+                Box::new(move |cancelled| solve(challenge, &input, &params, kind.runs(), cancelled))
+            }
+            JobKind::Test | JobKind::TestAll => {
+                let challenges = match kind {
+                    JobKind::Test => vec![(idx, challenge)],
+                    _ => Challenge::iter().enumerate().collect(),
+                };
+                let testdata_dir = self.config.testdata_dir.clone();
+
+                Box::new(move |cancelled| test(challenges, &testdata_dir, cancelled))
+            }
+        };
+
+        let (sender, receiver) = mpsc::channel();
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let worker_cancelled = Arc::clone(&cancelled);
+
+        thread::spawn(move || {
+            // A cancelled job has no result
+            if let Some(result) = work(&worker_cancelled) {
+                _ = sender.send(result);
+            }
+        });
+
+        self.job = Some(Job {
+            idx,
+            kind,
+            started: Instant::now(),
+            receiver,
+            cancelled,
+        });
+
+        Ok(())
+    }
+
+    fn cancel_job(&mut self) {
+        if let Some(job) = self.job.take() {
+            job.cancelled.store(true, Ordering::Relaxed);
+        }
+    }
+
+    /// Takes the result of the running job if it is done, or cancels it if it took too long.
+    fn finish_job(&mut self) -> Result<(), String> {
+        let Some(job) = &self.job else {
+            return Ok(());
+        };
+
+        let result = match job.receiver.try_recv() {
+            Ok(result) => result,
+            Err(TryRecvError::Empty) => {
+                let Some(timeout) = self.config.timeout else {
+                    return Ok(());
+                };
+
+                // Every benchmark run and every tested challenge gets the full timeout
+                let time_limit = timeout * job.kind.runs();
+
+                if job.started.elapsed() <= time_limit {
+                    return Ok(());
+                }
+
+                let what = match job.kind {
+                    JobKind::Run | JobKind::Benchmark => "Challenge",
+                    JobKind::Test | JobKind::TestAll => "Tests",
+                };
+
+                self.cancel_job();
+
+                return Err(format!(
+                    "{what} did not finish within {} seconds",
+                    time_limit.as_secs()
+                ));
+            }
+            Err(TryRecvError::Disconnected) => Err("Challenge panicked".to_string()),
+        };
+
+        let job = self.job.take().expect("Job is running");
+        let challenge = self.statuses[job.idx].challenge;
+
+        let (output, runtimes) = match result? {
+            JobOutput::Answers(output, runtimes) => (output, runtimes),
+            JobOutput::Tests(results) => {
+                self.show_tests(results);
+                return Ok(());
+            }
+        };
+
+        match job.kind {
+            JobKind::Run => {
+                let part_dir_path = self.part_dir_path(job.idx)?;
+                let timestamp = rundata::save_answer(&part_dir_path, &output)
+                    .map_err(|err| format!("Could not write answer: {err}"))?;
+                rundata::save_runtime(&part_dir_path, &timestamp, runtimes[0])
+                    .map_err(|err| format!("Could not write runtime: {err}"))?;
+                self.load_answers(job.idx)?;
+
+                self.message = format!("{challenge:?}: {output}");
+            }
+            JobKind::Benchmark => {
+                let fastest = runtimes.iter().min().copied().unwrap_or_default();
+                let mean = runtimes.iter().sum::<Duration>() / BENCHMARK_RUNS;
+                self.statuses[job.idx].benchmark = Some((fastest, mean));
+
+                self.message = format!("{challenge:?}: {BENCHMARK_RUNS} runs");
+            }
+            JobKind::Test | JobKind::TestAll => unreachable!("Tests have test results"),
+        }
+
+        Ok(())
+    }
+
+    fn show_tests(&mut self, results: Vec<(usize, TestResult)>) {
+        let (mut passed, mut total) = (0, 0);
+        let mut errors = Vec::new();
+
+        for (idx, tests) in results {
+            match &tests {
+                Ok((test_passed, test_count)) => {
+                    passed += test_passed;
+                    total += test_count;
+                }
+                Err(err) => errors.push(err.clone()),
+            }
+
+            self.statuses[idx].tests = Some(tests);
+        }
+
+        self.message = match errors.first() {
+            Some(err) => err.clone(),
+            None => format!("{passed} of {total} tests passed"),
+        };
+    }
+
+    fn mark_last_answer(&mut self, verdict: Verdict) -> Result<(), String> {
+        let idx = self.selected_idx();
+        let part_dir_path = self.part_dir_path(idx)?;
+
+        let last_answer = self.statuses[idx]
+            .answers
+            .last()
+            .ok_or("There is no answer to mark yet")?;
+
+        rundata::save_verdict(&part_dir_path, &last_answer.timestamp, verdict)
+            .map_err(|err| format!("Could not write verdict: {err}"))?;
+
+        self.load_answers(idx)
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [table_area, message_area, help_area] = Layout::vertical([
+            Constraint::Min(0),
+            Constraint::Length(1),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+
+        let header = Row::new([
+            "Day",
+            "Part",
+            "Tests",
+            "Last answer",
+            "Verdict",
+            "Runtime",
+            "Benchmark",
+        ])
+        .style(Style::new().add_modifier(Modifier::BOLD));
+
+        let rows = self.statuses.iter().map(|status| {
+            let (day_number, part_number) = status.challenge.day_part_number();
+            let last_answer = status.answers.last();
+
+            let (tests, tests_color) = match &status.tests {
+                None => ("-".to_string(), Color::Reset),
+                Some(Ok((passed, total))) if passed == total => {
+                    (format!("{passed}/{total}"), Color::Green)
+                }
+                Some(Ok((passed, total))) => (format!("{passed}/{total}"), Color::Red),
+                Some(Err(_)) => ("error".to_string(), Color::Red),
+            };
+
+            Row::new([
+                Line::from(day_number.to_string()),
+                Line::from(part_number.to_string()),
+                Line::from(tests).style(Style::new().fg(tests_color)),
+                Line::from(last_answer.map_or("-".to_string(), |answer| answer.answer.clone())),
+                Line::from(
+                    last_answer
+                        .and_then(|answer| answer.verdict)
+                        .map_or("-".to_string(), |verdict| verdict.to_string()),
+                ),
+                Line::from(format_duration(
+                    last_answer.and_then(|answer| answer.runtime),
+                )),
+                Line::from(status.benchmark.map_or("-".to_string(), |(fastest, mean)| {
+                    format!("{fastest:.2?} fastest, {mean:.2?} mean")
+                })),
+            ])
+        });
+
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(3),
+                Constraint::Length(4),
+                Constraint::Length(7),
+                Constraint::Min(16),
+                Constraint::Length(7),
+                Constraint::Length(10),
+                Constraint::Min(30),
+            ],
+        )
+        .header(header)
+        .row_highlight_style(Style::new().add_modifier(Modifier::REVERSED))
+        .block(Block::bordered().title("Advent of Code 2023"));
+
+        frame.render_stateful_widget(table, table_area, &mut self.table_state);
+        let message = match &self.job {
+            Some(job) if job.kind == JobKind::TestAll => format!(
+                "Testing all challenges for {:.1?}, Esc to cancel",
+                job.started.elapsed()
+            ),
+            Some(job) => format!(
+                "{:?}: running for {:.1?}, Esc to cancel",
+                self.statuses[job.idx].challenge,
+                job.started.elapsed()
+            ),
+            None => self.message.clone(),
+        };
+
+        frame.render_widget(Line::from(message), message_area);
+        frame.render_widget(
+            Line::from(HELP).style(Style::new().add_modifier(Modifier::DIM)),
+            help_area,
+        );
+
+        if self.show_history {
+            self.draw_history(frame, centered(frame.area()));
+        }
+    }
+
+    fn draw_history(&self, frame: &mut Frame, area: Rect) {
+        let status = &self.statuses[self.selected_idx()];
+        let (day_number, part_number) = status.challenge.day_part_number();
+
+        let items = status.answers.iter().rev().map(|answer| {
+            let verdict = answer
+                .verdict
+                .map_or(String::new(), |verdict| verdict.to_string());
+
+            format!(
+                "{}  {:<20}  {:<10}  {verdict}",
+                answer.timestamp,
+                answer.answer,
+                format_duration(answer.runtime)
+            )
+        });
+
+        let list = List::new(items).block(
+            Block::bordered().title(format!("Answers to day {day_number} part {part_number}")),
+        );
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(list, area);
+    }
+}
+
+/// Solves `challenge` `runs` times, or gives up if cancelled before a run.
+fn solve(
+    challenge: Challenge,
+    input: &str,
+    params: &aoc_2023::challenge::Params,
+    runs: u32,
+    cancelled: &AtomicBool,
+) -> Option<JobResult> {
+    let mut output = String::new();
+    let mut runtimes = Vec::new();
+
+    for _ in 0..runs {
+        if cancelled.load(Ordering::Relaxed) {
+            return None;
+        }
+
+        let start = Instant::now();
+
+        match challenge.run(input, params) {
+            Ok(answer) => output = answer,
+            Err(err) => return Some(Err(format!("Could not solve challenge: {err}"))),
+        }
+
+        runtimes.push(start.elapsed());
+    }
+
+    Some(Ok(JobOutput::Answers(output, runtimes)))
+}
+
+/// Runs the test cases of every challenge, or gives up if cancelled before a challenge.
+fn test(
+    challenges: Vec<(usize, Challenge)>,
+    testdata_dir: &Path,
+    cancelled: &AtomicBool,
+) -> Option<JobResult> {
+    let mut results = Vec::new();
+
+    for (idx, challenge) in challenges {
+        if cancelled.load(Ordering::Relaxed) {
+            return None;
+        }
+
+        let tests = aoc_2023::test_challenge(challenge, testdata_dir)
+            .map(|(test_count, failures)| (test_count - failures.len(), test_count))
+            .map_err(|err| format!("Could not run tests of {challenge:?}: {err}"));
+
+        results.push((idx, tests));
+    }
+
+    Some(Ok(JobOutput::Tests(results)))
+}
+
+fn format_duration(duration: Option<Duration>) -> String {
+    duration.map_or("-".to_string(), |duration| format!("{duration:.2?}"))
+}
+
+/// The middle of `area`, for popups.
+fn centered(area: Rect) -> Rect {
+    let [_, area, _] = Layout::vertical([
+        Constraint::Percentage(20),
+        Constraint::Percentage(60),
+        Constraint::Percentage(20),
+    ])
+    .areas(area);
+    let [_, area, _] = Layout::horizontal([
+        Constraint::Percentage(15),
+        Constraint::Percentage(70),
+        Constraint::Percentage(15),
+    ])
+    .areas(area);
+
+    area
+}
@@ -0,0 +1,47 @@
+//! JavaScript API for the solvers, for `wasm32-unknown-unknown` builds with the `wasm` feature.
+//! The library is only built as a `cdylib` for WebAssembly, e.g. with
+//! `cargo rustc --lib --release --crate-type cdylib --target wasm32-unknown-unknown --features wasm`
+//! followed by `wasm-bindgen --target web`.
+
+use crate::{challenge, Challenge};
+use wasm_bindgen::prelude::*;
+
+/// Solves `input` with the puzzle's rules and lenient parsing.
+/// Throws an `Error` if there is no challenge for `day` and `part`, or if it can't be solved.
+#[wasm_bindgen]
+pub fn solve(day: u8, part: u8, input: &str) -> Result<String, JsError> {
+    try_solve(day, part, input).map_err(|message| JsError::new(&message))
+}
+
+/// [`solve`] with the error message instead of a JavaScript error,
+/// which can only be created in JavaScript.
+fn try_solve(day: u8, part: u8, input: &str) -> Result<String, String> {
+    let challenge = Challenge::from_day_part(day, part)
+        .ok_or_else(|| format!("there is no challenge for day {day} part {part}"))?;
+
+    challenge
+        .run(input, &challenge::Params::default())
+        .map_err(|err| err.to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::wasm::{solve, try_solve};
+
+    #[test]
+    fn answer() {
+        assert_eq!(
+            solve(1, 1, "1abc2\npqr3stu8vwx").ok(),
+            Some("50".to_string())
+        );
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            try_solve(1, 3, ""),
+            Err("there is no challenge for day 1 part 3".to_string())
+        );
+        assert!(try_solve(2, 1, "Game one: 3 red").is_err());
+    }
+}